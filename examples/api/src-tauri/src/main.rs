@@ -9,6 +9,15 @@ fn greet(name: &str) -> String {
     format!("Hello {name}, You have been greeted from Rust!")
 }
 
+/// Sends the binary payload back as is, for checking large binary payloads and responses go through the IPC
+#[tauri::command]
+fn echo_payload(request: tauri::ipc::Request<'_>) -> tauri::ipc::Response {
+    match request.body() {
+        tauri::ipc::InvokeBody::Raw(bytes) => tauri::ipc::Response::new(bytes.clone()),
+        tauri::ipc::InvokeBody::Json(_) => tauri::ipc::Response::new(Vec::new()),
+    }
+}
+
 fn main() {
     tauri_runtime_verso::builder()
         .plugin(
@@ -17,13 +26,20 @@ fn main() {
                 .build(),
         )
         .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![greet])
+        .invoke_handler(tauri::generate_handler![greet, echo_payload])
         .setup(|app| {
             WebviewWindowBuilder::new(app, "main", Default::default())
                 .inner_size(900., 700.)
                 .decorations(false)
                 .build()?;
             tray::create_tray(app.handle())?;
+            // For checking the exit code is passed through, e.g. `API_EXAMPLE_EXIT_CODE=3 cargo run` should exit with 3
+            if let Some(code) = std::env::var("API_EXAMPLE_EXIT_CODE")
+                .ok()
+                .and_then(|code| code.parse().ok())
+            {
+                app.handle().exit(code);
+            }
             Ok(())
         })
         .run(tauri::generate_context!())
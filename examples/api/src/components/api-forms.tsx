@@ -12,6 +12,7 @@ export function FormsGroup() {
 		<div className="forms-group">
 			<HelloWorld />
 			<LoggingExample />
+			<LargePayloadExample />
 		</div>
 	)
 }
@@ -67,3 +68,26 @@ export function LoggingExample() {
 		</div>
 	)
 }
+
+export function LargePayloadExample() {
+	const [message, setMessage] = useState('')
+	return (
+		<div className="form-and-message">
+			<h2>Large Payload Example</h2>
+			<button
+				onClick={async () => {
+					const payload = new Uint8Array(8 * 1024 * 1024).map((_, i) => i % 256)
+					const response = new Uint8Array(await invoke<ArrayBuffer>('echo_payload', payload))
+					const matches =
+						response.length === payload.length && response.every((byte, i) => byte === payload[i])
+					setMessage(
+						`Sent ${payload.length} bytes, received ${response.length} bytes back, ${matches ? 'matching' : 'NOT matching'} the payload`
+					)
+				}}
+			>
+				Send 8 MiB
+			</button>
+			<div className="message">{message}</div>
+		</div>
+	)
+}
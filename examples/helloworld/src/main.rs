@@ -7,16 +7,21 @@ fn greet(name: &str) -> String {
     format!("Hello {name}, You have been greeted from Rust!")
 }
 
-fn main() {
-    // You can also set the `versoview` executable path yourself
-    // tauri_runtime_verso::set_verso_path("../verso/target/debug/versoview");
+#[tauri::command]
+fn window_label(window: tauri_runtime_verso::WebviewWindow) -> String {
+    window.label().to_owned()
+}
 
-    // To use the devtools, set it like to let verso open a devtools server,
-    // and you can connect to it through Firefox's devtools in the `about:debugging` page
-    tauri_runtime_verso::set_verso_devtools_port(1234);
+fn main() {
+    let config = tauri_runtime_verso::VersoRuntimeConfig::new()
+        // You can also set the `versoview` executable path yourself
+        // .verso_path("../verso/target/debug/versoview")
+        // To use the devtools, set it like to let verso open a devtools server,
+        // and you can connect to it through Firefox's devtools in the `about:debugging` page
+        .devtools_port(1234);
 
-    tauri_runtime_verso::builder()
-        .invoke_handler(tauri::generate_handler![greet])
+    tauri_runtime_verso::builder_with_config(config)
+        .invoke_handler(tauri::generate_handler![greet, window_label])
         .setup(|app| {
             dbg!(app.get_webview_window("main").unwrap().inner_size()).unwrap();
             Ok(())
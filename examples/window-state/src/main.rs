@@ -0,0 +1,9 @@
+#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+
+fn main() {
+    tauri_runtime_verso::builder()
+        // Saves the window state when the app exits and restores it when the window is created
+        .plugin(tauri_plugin_window_state::Builder::new().build())
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application")
+}
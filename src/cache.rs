@@ -0,0 +1,299 @@
+//! In-memory cache for custom protocol responses,
+//! see [`VersoRuntimeConfig::protocol_response_cache`](crate::VersoRuntimeConfig::protocol_response_cache)
+
+use http::{HeaderMap, HeaderValue, Method, Request, Response, StatusCode, header};
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// Responses larger than this are not cached
+const MAX_ENTRY_SIZE: usize = 8 * 1024 * 1024;
+/// The cache is cleared when the cached bodies exceed this in total
+const MAX_TOTAL_SIZE: usize = 64 * 1024 * 1024;
+
+struct CacheEntry {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Vec<u8>,
+    expires_at: Instant,
+    etag: Option<HeaderValue>,
+}
+
+impl CacheEntry {
+    fn to_response(&self) -> Response<Vec<u8>> {
+        let mut response = Response::new(self.body.clone());
+        *response.status_mut() = self.status;
+        *response.headers_mut() = self.headers.clone();
+        response
+    }
+}
+
+/// The result of looking up a request in the [`ResponseCache`]
+pub(crate) enum CacheLookup {
+    /// The cached response is fresh, respond with it without calling the handler
+    Fresh(Response<Vec<u8>>),
+    /// The cached response is stale and `If-None-Match` is added to the request to revalidate it,
+    /// this is the request without it, to send again if the cached response is gone
+    /// by the time the handler responds with `304 Not Modified`, see [`ResponseCache::lost_revalidated`]
+    Revalidate(Request<Vec<u8>>),
+    /// Nothing to use from the cache, the request goes to the handler as is
+    Miss,
+}
+
+/// Caches the `200 OK` responses of `GET` requests by URI,
+/// following the `Cache-Control` and `ETag` headers of the response:
+///
+/// - `no-store` responses are never cached
+/// - responses are served from the cache without calling the handler until `max-age` runs out
+/// - after that (or right away with `no-cache`), the request is sent to the handler with `If-None-Match`,
+///   and the cached response is used if the handler responds with `304 Not Modified`
+/// - responses without `max-age` or `ETag` are not cached
+#[derive(Default)]
+pub(crate) struct ResponseCache {
+    entries: HashMap<String, CacheEntry>,
+    total_size: usize,
+}
+
+impl ResponseCache {
+    /// Returns the key for the request if its response can be cached
+    pub(crate) fn key(request: &Request<Vec<u8>>) -> Option<String> {
+        (request.method() == Method::GET && !request.headers().contains_key(header::RANGE))
+            .then(|| request.uri().to_string())
+    }
+
+    /// Returns the cached response if it's still fresh,
+    /// otherwise adds `If-None-Match` to the request if there's a stale one to revalidate
+    ///
+    /// Requests with `Cache-Control: no-cache` (e.g. from a reload ignoring the cache) always go to the handler
+    pub(crate) fn get(&self, key: &str, request: &mut Request<Vec<u8>>) -> CacheLookup {
+        if has_directive(request.headers(), "no-cache") {
+            return CacheLookup::Miss;
+        }
+        let Some(entry) = self.entries.get(key) else {
+            return CacheLookup::Miss;
+        };
+        if entry.expires_at > Instant::now() {
+            return CacheLookup::Fresh(entry.to_response());
+        }
+        let Some(etag) = &entry.etag else {
+            return CacheLookup::Miss;
+        };
+        let retry_request = clone_request(request);
+        request
+            .headers_mut()
+            .insert(header::IF_NONE_MATCH, etag.clone());
+        CacheLookup::Revalidate(retry_request)
+    }
+
+    /// Returns if the response is a `304 Not Modified` to our revalidation but the cached response is gone,
+    /// e.g. it's evicted while the handler was running, the request needs to be sent again without `If-None-Match` then
+    pub(crate) fn lost_revalidated(&self, key: &str, response: &Response<Vec<u8>>) -> bool {
+        response.status() == StatusCode::NOT_MODIFIED && !self.entries.contains_key(key)
+    }
+
+    /// Stores the response from the handler if it's cacheable,
+    /// and swaps a `304 Not Modified` for the cached response,
+    /// check [`ResponseCache::lost_revalidated`] first if the request is a revalidation
+    pub(crate) fn update(&mut self, key: &str, response: Response<Vec<u8>>) -> Response<Vec<u8>> {
+        let max_age = parse_max_age(response.headers());
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(entry) = self.entries.get_mut(key) {
+                entry.expires_at = Instant::now() + max_age.unwrap_or_default();
+                return entry.to_response();
+            }
+            return response;
+        }
+
+        self.remove(key);
+        let etag = response.headers().get(header::ETAG).cloned();
+        let cacheable = response.status() == StatusCode::OK
+            && !has_directive(response.headers(), "no-store")
+            && (max_age.is_some() || etag.is_some())
+            && response.body().len() <= MAX_ENTRY_SIZE;
+        if !cacheable {
+            return response;
+        }
+
+        let max_age = if has_directive(response.headers(), "no-cache") {
+            Duration::ZERO
+        } else {
+            max_age.unwrap_or_default()
+        };
+        if self.total_size + response.body().len() > MAX_TOTAL_SIZE {
+            self.evict();
+        }
+        self.total_size += response.body().len();
+        self.entries.insert(
+            key.to_owned(),
+            CacheEntry {
+                status: response.status(),
+                headers: response.headers().clone(),
+                body: response.body().clone(),
+                expires_at: Instant::now() + max_age,
+                etag,
+            },
+        );
+        response
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.total_size -= entry.body.len();
+        }
+    }
+
+    /// Removes the expired entries that can't be revalidated, and everything if that's not enough
+    fn evict(&mut self) {
+        let now = Instant::now();
+        self.entries
+            .retain(|_, entry| entry.expires_at > now || entry.etag.is_some());
+        self.total_size = self.entries.values().map(|entry| entry.body.len()).sum();
+        if self.total_size > MAX_TOTAL_SIZE / 2 {
+            self.entries.clear();
+            self.total_size = 0;
+        }
+    }
+}
+
+fn clone_request(request: &Request<Vec<u8>>) -> Request<Vec<u8>> {
+    let mut clone = Request::new(request.body().clone());
+    *clone.method_mut() = request.method().clone();
+    *clone.uri_mut() = request.uri().clone();
+    *clone.version_mut() = request.version();
+    *clone.headers_mut() = request.headers().clone();
+    clone
+}
+
+fn cache_control_directives(headers: &HeaderMap) -> impl Iterator<Item = &str> {
+    headers
+        .get_all(header::CACHE_CONTROL)
+        .into_iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+}
+
+fn has_directive(headers: &HeaderMap, directive: &str) -> bool {
+    cache_control_directives(headers).any(|value| value.eq_ignore_ascii_case(directive))
+}
+
+fn parse_max_age(headers: &HeaderMap) -> Option<Duration> {
+    cache_control_directives(headers).find_map(|directive| {
+        let (name, value) = directive.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("max-age")
+            .then(|| value.trim().trim_matches('"').parse().ok())
+            .flatten()
+            .map(Duration::from_secs)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URI: &str = "asset://localhost/index.js";
+
+    fn request() -> Request<Vec<u8>> {
+        Request::get(URI).body(Vec::new()).unwrap()
+    }
+
+    fn response(status: StatusCode, cache_control: &str, body: &[u8]) -> Response<Vec<u8>> {
+        Response::builder()
+            .status(status)
+            .header(header::CACHE_CONTROL, cache_control)
+            .header(header::ETAG, "\"v1\"")
+            .body(body.to_vec())
+            .unwrap()
+    }
+
+    /// Sends the request through the cache like the runtime does, calling `handler` if it's not served from the cache
+    fn fetch(
+        cache: &mut ResponseCache,
+        handler: &mut impl FnMut(&Request<Vec<u8>>) -> Response<Vec<u8>>,
+    ) -> Response<Vec<u8>> {
+        let mut request = request();
+        let key = ResponseCache::key(&request).unwrap();
+        match cache.get(&key, &mut request) {
+            CacheLookup::Fresh(response) => response,
+            CacheLookup::Revalidate(retry_request) => {
+                let response = handler(&request);
+                if cache.lost_revalidated(&key, &response) {
+                    let response = handler(&retry_request);
+                    return cache.update(&key, response);
+                }
+                cache.update(&key, response)
+            }
+            CacheLookup::Miss => {
+                let response = handler(&request);
+                cache.update(&key, response)
+            }
+        }
+    }
+
+    #[test]
+    fn fresh_response_calls_the_handler_once() {
+        let mut cache = ResponseCache::default();
+        let mut calls = 0;
+        let mut handler = |_: &Request<Vec<u8>>| {
+            calls += 1;
+            response(StatusCode::OK, "max-age=60", b"body")
+        };
+        for _ in 0..3 {
+            let response = fetch(&mut cache, &mut handler);
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.body(), b"body");
+        }
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn no_store_is_not_cached() {
+        let mut cache = ResponseCache::default();
+        let mut calls = 0;
+        let mut handler = |_: &Request<Vec<u8>>| {
+            calls += 1;
+            response(StatusCode::OK, "no-store, max-age=60", b"body")
+        };
+        fetch(&mut cache, &mut handler);
+        fetch(&mut cache, &mut handler);
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn stale_response_is_revalidated() {
+        let mut cache = ResponseCache::default();
+        let mut handler = |request: &Request<Vec<u8>>| {
+            if request.headers().get(header::IF_NONE_MATCH).is_some() {
+                response(StatusCode::NOT_MODIFIED, "no-cache", b"")
+            } else {
+                response(StatusCode::OK, "no-cache", b"body")
+            }
+        };
+        fetch(&mut cache, &mut handler);
+        let response = fetch(&mut cache, &mut handler);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.body(), b"body");
+    }
+
+    #[test]
+    fn not_modified_without_the_cached_response_is_retried() {
+        let mut cache = ResponseCache::default();
+        let key = ResponseCache::key(&request()).unwrap();
+        cache.update(&key, response(StatusCode::OK, "no-cache", b"body"));
+
+        let mut request = request();
+        let CacheLookup::Revalidate(retry_request) = cache.get(&key, &mut request) else {
+            panic!("expected a revalidation");
+        };
+        assert!(request.headers().contains_key(header::IF_NONE_MATCH));
+        assert!(!retry_request.headers().contains_key(header::IF_NONE_MATCH));
+
+        // Evicted while the handler is running
+        cache.remove(&key);
+        let not_modified = response(StatusCode::NOT_MODIFIED, "no-cache", b"");
+        assert!(cache.lost_revalidated(&key, &not_modified));
+    }
+}
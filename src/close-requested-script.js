@@ -0,0 +1,46 @@
+// Lets the page delay or cancel closing the window, see `VersoWindowBuilder::close_requested_bridge`
+//
+// window.addEventListener('tauri-verso://close-requested', (event) => {
+// 	// Cancel the close right away
+// 	event.preventDefault()
+// 	// Or delay it until the promise settles, resolving to `false` or rejecting cancels the close
+// 	event.waitUntil(flushToServer())
+// })
+
+;(function () {
+	let state = 'idle'
+
+	Object.defineProperty(window, '__TAURI_VERSO_CLOSE_REQUESTED__', {
+		value: Object.freeze({
+			request() {
+				if (state === 'pending') {
+					return state
+				}
+				const pending = []
+				const event = new CustomEvent('tauri-verso://close-requested', { cancelable: true })
+				event.waitUntil = (promise) => pending.push(Promise.resolve(promise))
+				window.dispatchEvent(event)
+
+				if (event.defaultPrevented) {
+					state = 'cancel'
+				} else if (pending.length === 0) {
+					state = 'allow'
+				} else {
+					state = 'pending'
+					Promise.all(pending).then(
+						(results) => {
+							state = results.includes(false) ? 'cancel' : 'allow'
+						},
+						() => {
+							state = 'cancel'
+						}
+					)
+				}
+				return state
+			},
+			state() {
+				return state
+			},
+		}),
+	})
+})()
@@ -0,0 +1,223 @@
+use std::{
+    fmt::{self, Debug},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use crate::{StreamBody, StreamingProtocolHandler};
+
+/// The configuration of a [`VersoRuntime`](crate::VersoRuntime), use it with [`builder_with_config`](crate::builder_with_config)
+///
+/// The options not set here fall back to the ones set through the deprecated process-wide setters
+/// like [`set_verso_path`](crate::set_verso_path), so each runtime can have its own configuration
+#[derive(Debug, Clone)]
+pub struct VersoRuntimeConfig {
+    pub(crate) verso_path: Option<PathBuf>,
+    pub(crate) resource_directory: Option<PathBuf>,
+    pub(crate) devtools_port: Option<u16>,
+    pub(crate) headless: bool,
+    pub(crate) capture_output: bool,
+    pub(crate) auto_restart: bool,
+    pub(crate) on_verso_exited: Option<VersoExitedHandler>,
+    pub(crate) reposition_on_monitor_removed: bool,
+    pub(crate) exit_process: bool,
+    pub(crate) streaming_protocols: Vec<(String, StreamingProtocol)>,
+    pub(crate) cached_protocols: Vec<String>,
+    pub(crate) app_id: Option<String>,
+}
+
+#[derive(Clone)]
+pub(crate) struct VersoExitedHandler(pub(crate) Arc<dyn Fn(&str) + Send + Sync>);
+
+impl Debug for VersoExitedHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VersoExitedHandler").finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct StreamingProtocol(pub(crate) Arc<StreamingProtocolHandler>);
+
+impl Debug for StreamingProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamingProtocol").finish_non_exhaustive()
+    }
+}
+
+impl Default for VersoRuntimeConfig {
+    fn default() -> Self {
+        Self {
+            verso_path: None,
+            resource_directory: None,
+            devtools_port: None,
+            headless: false,
+            capture_output: true,
+            auto_restart: false,
+            on_verso_exited: None,
+            reposition_on_monitor_removed: true,
+            exit_process: false,
+            streaming_protocols: Vec::new(),
+            cached_protocols: Vec::new(),
+            app_id: None,
+        }
+    }
+}
+
+impl VersoRuntimeConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the Verso executable path to use for the webviews,
+    /// this can also be a directory containing the `versoview(.exe)` executable
+    ///
+    /// If this is not set, versoview is looked up next to the current executable (the `externalBin` setup),
+    /// then at the `VERSOVIEW_PATH` environment variable, and then on `PATH`
+    pub fn verso_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.verso_path = Some(path.into());
+        self
+    }
+
+    /// Sets the Verso resources directory to use for the webviews
+    pub fn resource_directory(mut self, path: impl Into<PathBuf>) -> Self {
+        self.resource_directory = Some(path.into());
+        self
+    }
+
+    /// Sets the devtools port to open for every webview, 0 for a random port
+    ///
+    /// Since Verso doesn't have devtools built-in,
+    /// you need to use the one from Firefox from the `about:debugging` page,
+    /// this setting allows you to let verso open a port for it
+    pub fn devtools_port(mut self, port: u16) -> Self {
+        self.devtools_port = Some(port);
+        self
+    }
+
+    /// Sets whether to run every window headless, defaults to `false`,
+    /// see [`VersoWindowBuilder::headless`](crate::VersoWindowBuilder::headless)
+    pub fn headless(mut self, headless: bool) -> Self {
+        self.headless = headless;
+        self
+    }
+
+    /// Sets whether to capture the stdout and stderr of the versoview processes
+    /// and forward them to the [`log`] crate with the window label as the target, defaults to `true`,
+    /// see [`VersoWindowBuilder::capture_output`](crate::VersoWindowBuilder::capture_output) to set it per window
+    pub fn capture_output(mut self, capture: bool) -> Self {
+        self.capture_output = capture;
+        self
+    }
+
+    /// Sets whether to restart the versoview process of a window at its last URL
+    /// if it exits unexpectedly, defaults to `false`
+    ///
+    /// Restarts are delayed with an exponential backoff,
+    /// and we give up if the process keeps exiting shortly after each restart
+    pub fn auto_restart(mut self, auto_restart: bool) -> Self {
+        self.auto_restart = auto_restart;
+        self
+    }
+
+    /// Sets a handler to be called with the window label
+    /// when the versoview process of a window exits unexpectedly
+    ///
+    /// ### Example:
+    ///
+    /// ```
+    /// let config = tauri_runtime_verso::VersoRuntimeConfig::new().on_verso_exited(|label| {
+    ///     eprintln!("The webview of window {label} crashed");
+    /// });
+    /// ```
+    pub fn on_verso_exited(mut self, handler: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.on_verso_exited = Some(VersoExitedHandler(Arc::new(handler)));
+        self
+    }
+
+    /// Sets whether to move the windows onto the work area of a remaining monitor
+    /// when the monitor they're on is removed, a [`WindowEvent::Moved`](tauri_runtime::window::WindowEvent::Moved)
+    /// is emitted for the moved windows, defaults to `true`
+    pub fn reposition_on_monitor_removed(mut self, reposition: bool) -> Self {
+        self.reposition_on_monitor_removed = reposition;
+        self
+    }
+
+    /// Sets whether [`tauri::App::run`] exits the process with the exit code
+    /// (e.g. the one passed to [`tauri::AppHandle::exit`]) after the event loop ends, defaults to `false`,
+    /// which returns from [`tauri::App::run`] so you can do something after the app exits,
+    /// use [`tauri::App::run_return`] if you need the exit code
+    pub fn exit_process(mut self, exit_process: bool) -> Self {
+        self.exit_process = exit_process;
+        self
+    }
+
+    /// Registers a custom protocol that responds with a seekable body instead of the whole body in memory,
+    /// the handler is called with the webview label and the request on one of a few shared background threads,
+    /// and only the part requested by the `Range` header (or the first few megabytes without one) is read from the body,
+    /// this makes serving large files like videos possible and seeking in `<video>` and `<audio>` work
+    ///
+    /// ### Example:
+    ///
+    /// ```no_run
+    /// let config = tauri_runtime_verso::VersoRuntimeConfig::new().streaming_protocol(
+    ///     "stream",
+    ///     |_label, request| {
+    ///         let path = request.uri().path().trim_start_matches('/');
+    ///         match std::fs::File::open(path) {
+    ///             Ok(file) => http::Response::new(Box::new(file) as Box<dyn tauri_runtime_verso::StreamBody>),
+    ///             Err(_) => http::Response::builder()
+    ///                 .status(404)
+    ///                 .body(Box::new(std::io::empty()) as Box<dyn tauri_runtime_verso::StreamBody>)
+    ///                 .unwrap(),
+    ///         }
+    ///     },
+    /// );
+    /// ```
+    pub fn streaming_protocol(
+        mut self,
+        scheme: impl Into<String>,
+        handler: impl Fn(&str, &http::Request<Vec<u8>>) -> http::Response<Box<dyn StreamBody>>
+        + Send
+        + Sync
+        + 'static,
+    ) -> Self {
+        self.streaming_protocols
+            .push((scheme.into(), StreamingProtocol(Arc::new(handler))));
+        self
+    }
+
+    /// Enables an in-memory cache for the responses of the custom protocol `scheme`,
+    /// so repeated requests for the same asset skip calling the protocol handler on the main thread,
+    /// each webview has its own cache
+    ///
+    /// Only `200 OK` responses to `GET` requests are cached, following the `Cache-Control` and `ETag` headers they come with,
+    /// a response is reused until its `max-age` runs out, after that (or right away with `no-cache`)
+    /// the handler is called with `If-None-Match` and can respond with `304 Not Modified` to keep using it,
+    /// responses with `no-store` or without either `max-age` or `ETag` are never cached
+    ///
+    /// ### Example:
+    ///
+    /// ```no_run
+    /// let config = tauri_runtime_verso::VersoRuntimeConfig::new().protocol_response_cache("asset");
+    /// ```
+    pub fn protocol_response_cache(mut self, scheme: impl Into<String>) -> Self {
+        let scheme = scheme.into();
+        if !self.cached_protocols.contains(&scheme) {
+            self.cached_protocols.push(scheme);
+        }
+        self
+    }
+
+    /// Sets the app id on the windows, on Wayland, this needs to match the basename of your `.desktop` file
+    /// for the desktop environment to show the correct icon in the taskbar
+    ///
+    /// This takes precedence over the app id tauri sets from `app > enableGTKAppId` in the tauri config
+    ///
+    /// ## Platform-specific
+    ///
+    /// **Windows / macOS**: Unsupported, has no effect
+    pub fn app_id(mut self, app_id: impl Into<String>) -> Self {
+        self.app_id = Some(app_id.into());
+        self
+    }
+}
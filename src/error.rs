@@ -0,0 +1,89 @@
+use std::{fmt, path::PathBuf};
+
+/// The errors specific to this runtime, converted to [`tauri_runtime::Error::CreateWebview`]
+/// when returned through the Tauri APIs, so they can be downcast from there
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum VersoRuntimeError {
+    /// The Verso path is not set and versoview can't be found next to the executable,
+    /// at the `VERSOVIEW_PATH` environment variable, or on `PATH`
+    VersoviewNotFound,
+    /// The Verso path (see [`VersoRuntimeConfig::verso_path`](crate::VersoRuntimeConfig::verso_path)) doesn't exist
+    VersoPathNotFound(PathBuf),
+    /// The Verso path is a directory and doesn't contain a `versoview` executable
+    VersoviewNotInDirectory(PathBuf),
+    /// The `versoview` file is not executable
+    VersoviewNotExecutable(PathBuf),
+    /// The version of `versoview` is not supported by this crate
+    UnsupportedVersion { found: String, supported: String },
+    /// Failed to set up the `versoview` process after spawning it, with the reason
+    Spawn(String),
+    /// The config from [`builder_with_config`](crate::builder_with_config) couldn't be applied to the runtime, with the reason
+    Config(String),
+}
+
+impl fmt::Display for VersoRuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::VersoviewNotFound => f.write_str(
+                "Couldn't find versoview, set its path with `VersoRuntimeConfig::verso_path` or the `VERSOVIEW_PATH` environment variable, or put it next to the executable or on `PATH`",
+            ),
+            Self::VersoPathNotFound(path) => {
+                write!(f, "The Verso path {} doesn't exist", path.display())
+            }
+            Self::VersoviewNotInDirectory(path) => write!(
+                f,
+                "The Verso path {} is a directory and doesn't contain a versoview executable",
+                path.display()
+            ),
+            Self::VersoviewNotExecutable(path) => {
+                write!(f, "The Verso path {} is not executable", path.display())
+            }
+            Self::UnsupportedVersion { found, supported } => write!(
+                f,
+                "versoview {found} is not supported, this version of tauri-runtime-verso works with versoview {supported}"
+            ),
+            Self::Spawn(reason) => write!(f, "Failed to set up versoview: {reason}"),
+            Self::Config(reason) => write!(f, "Failed to apply the runtime config: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for VersoRuntimeError {}
+
+impl From<VersoRuntimeError> for tauri_runtime::Error {
+    fn from(error: VersoRuntimeError) -> Self {
+        Self::CreateWebview(Box::new(error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_convert_to_and_from_tauri_errors() {
+        let errors = [
+            VersoRuntimeError::VersoviewNotFound,
+            VersoRuntimeError::VersoPathNotFound("verso".into()),
+            VersoRuntimeError::VersoviewNotInDirectory("verso".into()),
+            VersoRuntimeError::VersoviewNotExecutable("versoview".into()),
+            VersoRuntimeError::UnsupportedVersion {
+                found: "0.0.1".to_owned(),
+                supported: "0.0.9".to_owned(),
+            },
+            VersoRuntimeError::Spawn("no IPC channel".to_owned()),
+            VersoRuntimeError::Config("no runtime".to_owned()),
+        ];
+        for error in errors {
+            let message = error.to_string();
+            match tauri_runtime::Error::from(error) {
+                tauri_runtime::Error::CreateWebview(error) => {
+                    let error = error.downcast::<VersoRuntimeError>().unwrap();
+                    assert_eq!(error.to_string(), message);
+                }
+                error => panic!("expected `CreateWebview`, got {error:?}"),
+            }
+        }
+    }
+}
@@ -1,13 +1,14 @@
 use tao::event_loop::EventLoopWindowTarget as TaoEventLoopWindowTarget;
 use tauri_runtime::{Error, Result, dpi::PhysicalPosition, monitor::Monitor};
 
-use crate::monitor::MonitorExt;
+use crate::monitor::{MonitorExt, VideoMode};
 
 pub trait TaoEventLoopWindowTargetExt {
     fn tauri_primary_monitor(&self) -> Option<Monitor>;
     fn tauri_monitor_from_point(&self, x: f64, y: f64) -> Option<Monitor>;
     fn tauri_available_monitors(&self) -> Vec<Monitor>;
     fn tauri_cursor_position(&self) -> Result<PhysicalPosition<f64>>;
+    fn tauri_monitor_video_modes(&self, monitor: &Monitor) -> Option<Vec<VideoMode>>;
 }
 
 impl<T> TaoEventLoopWindowTargetExt for TaoEventLoopWindowTarget<T> {
@@ -32,8 +33,19 @@ impl<T> TaoEventLoopWindowTargetExt for TaoEventLoopWindowTarget<T> {
             .map_err(|_| Error::FailedToGetCursorPosition)?;
         Ok(position)
     }
+
+    fn tauri_monitor_video_modes(&self, monitor: &Monitor) -> Option<Vec<VideoMode>> {
+        let tao_monitor = self.available_monitors().find(|tao_monitor| {
+            tao_monitor.name() == monitor.name
+                && tao_monitor.position() == monitor.position
+                && tao_monitor.size() == monitor.size
+        })?;
+        Some(tao_monitor.video_modes().map(VideoMode::from).collect())
+    }
 }
 
+/// Converts to a Tauri [`Monitor`], including the work area (the area without the taskbar, dock, etc.)
+/// from [`MonitorExt::work_area`]
 pub fn tao_monitor_to_tauri_monitor(monitor: tao::monitor::MonitorHandle) -> Monitor {
     Monitor {
         work_area: monitor.work_area(),
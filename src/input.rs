@@ -0,0 +1,245 @@
+//! Synthetic input events for automating webviews,
+//! these are dispatched as DOM events inside the page instead of going through the OS
+
+use tauri_runtime::dpi::LogicalPosition;
+
+use crate::utils::to_js_string;
+
+/// The mouse button of a [`MouseEvent`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MouseButton {
+    #[default]
+    Left,
+    Middle,
+    Right,
+}
+
+impl MouseButton {
+    /// The value of `MouseEvent.button` in the DOM
+    fn button(self) -> u8 {
+        match self {
+            Self::Left => 0,
+            Self::Middle => 1,
+            Self::Right => 2,
+        }
+    }
+
+    /// The value of `MouseEvent.buttons` in the DOM
+    fn buttons(self) -> u8 {
+        match self {
+            Self::Left => 1,
+            Self::Middle => 4,
+            Self::Right => 2,
+        }
+    }
+}
+
+/// The kind of a [`MouseEvent`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MouseEventKind {
+    Down,
+    Up,
+    Move,
+    /// A `mousedown`, `mouseup` then `click`
+    #[default]
+    Click,
+    /// Two clicks followed by a `dblclick`
+    DoubleClick,
+}
+
+/// Modifier keys held during a synthetic input event
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub meta: bool,
+}
+
+/// A synthetic mouse event,
+/// dispatched to the element at [`MouseEvent::position`]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MouseEvent {
+    pub kind: MouseEventKind,
+    pub button: MouseButton,
+    /// Relative to the top-left corner of the webview
+    pub position: LogicalPosition<f64>,
+    pub modifiers: Modifiers,
+}
+
+/// The kind of a [`KeyEvent`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeyEventKind {
+    Down,
+    Up,
+    /// A `keydown` then `keyup`
+    #[default]
+    Press,
+}
+
+/// A synthetic keyboard event,
+/// dispatched to the focused element (`document.activeElement`)
+///
+/// Note the browser doesn't perform default actions for synthetic events,
+/// e.g. pressing a character key won't insert it into an input element
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyEvent {
+    pub kind: KeyEventKind,
+    /// The value of `KeyboardEvent.key`, e.g. `a` or `Enter`
+    pub key: String,
+    /// The value of `KeyboardEvent.code`, e.g. `KeyA` or `Enter`
+    pub code: String,
+    pub modifiers: Modifiers,
+}
+
+impl Modifiers {
+    fn to_js_init(self) -> String {
+        format!(
+            "shiftKey: {}, ctrlKey: {}, altKey: {}, metaKey: {}",
+            self.shift, self.ctrl, self.alt, self.meta
+        )
+    }
+}
+
+impl MouseEvent {
+    pub(crate) fn to_script(&self) -> String {
+        let types: &[&str] = match self.kind {
+            MouseEventKind::Down => &["mousedown"],
+            MouseEventKind::Up => &["mouseup"],
+            MouseEventKind::Move => &["mousemove"],
+            MouseEventKind::Click => &["mousedown", "mouseup", "click"],
+            MouseEventKind::DoubleClick => &[
+                "mousedown",
+                "mouseup",
+                "click",
+                "mousedown",
+                "mouseup",
+                "click",
+                "dblclick",
+            ],
+        };
+        let types = types
+            .iter()
+            .map(|event_type| to_js_string(event_type))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let LogicalPosition { x, y } = self.position;
+        let buttons = if self.kind == MouseEventKind::Down {
+            self.button.buttons()
+        } else {
+            0
+        };
+        format!(
+            r#"(() => {{
+    const target = document.elementFromPoint({x}, {y}) || document.body;
+    const init = {{ bubbles: true, cancelable: true, composed: true, view: window, clientX: {x}, clientY: {y}, button: {button}, buttons: {buttons}, {modifiers} }};
+    for (const type of [{types}]) {{
+        if (type === "mousedown" && typeof target.focus === "function") target.focus();
+        target.dispatchEvent(new MouseEvent(type, init));
+    }}
+}})();"#,
+            button = self.button.button(),
+            modifiers = self.modifiers.to_js_init(),
+        )
+    }
+}
+
+impl KeyEvent {
+    pub(crate) fn to_script(&self) -> String {
+        let types = match self.kind {
+            KeyEventKind::Down => r#""keydown""#,
+            KeyEventKind::Up => r#""keyup""#,
+            KeyEventKind::Press => r#""keydown", "keyup""#,
+        };
+        format!(
+            r#"(() => {{
+    const target = document.activeElement || document.body;
+    const init = {{ bubbles: true, cancelable: true, composed: true, view: window, key: {key}, code: {code}, {modifiers} }};
+    for (const type of [{types}]) {{
+        target.dispatchEvent(new KeyboardEvent(type, init));
+    }}
+}})();"#,
+            key = to_js_string(&self.key),
+            code = to_js_string(&self.code),
+            modifiers = self.modifiers.to_js_init(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn click_dispatches_down_up_and_click_at_the_position() {
+        let script = MouseEvent {
+            position: LogicalPosition::new(12.5, 40.0),
+            ..Default::default()
+        }
+        .to_script();
+        assert!(script.contains(r#"for (const type of ["mousedown", "mouseup", "click"])"#));
+        assert!(script.contains("document.elementFromPoint(12.5, 40)"));
+        assert!(script.contains("clientX: 12.5, clientY: 40, button: 0, buttons: 0"));
+    }
+
+    #[test]
+    fn mouse_down_holds_the_button() {
+        let script = MouseEvent {
+            kind: MouseEventKind::Down,
+            button: MouseButton::Right,
+            ..Default::default()
+        }
+        .to_script();
+        assert!(script.contains(r#"for (const type of ["mousedown"])"#));
+        assert!(script.contains("button: 2, buttons: 2"));
+    }
+
+    #[test]
+    fn double_click_ends_with_dblclick() {
+        let script = MouseEvent {
+            kind: MouseEventKind::DoubleClick,
+            ..Default::default()
+        }
+        .to_script();
+        assert!(script.contains(r#""click", "mousedown", "mouseup", "click", "dblclick"])"#));
+    }
+
+    #[test]
+    fn modifiers_are_passed_to_the_event() {
+        let script = MouseEvent {
+            modifiers: Modifiers {
+                shift: true,
+                meta: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+        .to_script();
+        assert!(script.contains("shiftKey: true, ctrlKey: false, altKey: false, metaKey: true"));
+    }
+
+    #[test]
+    fn key_press_dispatches_down_and_up() {
+        let script = KeyEvent {
+            key: "Enter".to_owned(),
+            code: "Enter".to_owned(),
+            ..Default::default()
+        }
+        .to_script();
+        assert!(script.contains(r#"for (const type of ["keydown", "keyup"])"#));
+        assert!(script.contains(r#"key: "Enter", code: "Enter""#));
+    }
+
+    #[test]
+    fn key_is_escaped() {
+        let script = KeyEvent {
+            kind: KeyEventKind::Down,
+            key: r#"""#.to_owned(),
+            code: "Quote".to_owned(),
+            ..Default::default()
+        }
+        .to_script();
+        assert!(script.contains(r#"for (const type of ["keydown"])"#));
+        assert!(script.contains(r#"key: "\"", code: "Quote""#));
+    }
+}
@@ -1,18 +1,17 @@
 // This file is copied and modified from Tauri with a few modifications
-// - Changed `processIpcMessage` to always return a string so we can put it inside of http request header
-// - Changed custom protocol IPC to use header instead of body since we can't get the body in Servo yet
+// - Inlined `processIpcMessage` from process-ipc-message-fn.js
 //
 // > ipc-protocol.js: https://github.com/tauri-apps/tauri/blob/dev/crates/tauri/scripts/ipc-protocol.js
 // > process-ipc-message-fn.js: https://github.com/tauri-apps/tauri/blob/dev/crates/tauri/scripts/process-ipc-message-fn.js
 
 ;(function () {
 	const processIpcMessage = function (message) {
-		// if (message instanceof ArrayBuffer || ArrayBuffer.isView(message) || Array.isArray(message)) {
-		// 	return {
-		// 		contentType: 'application/octet-stream',
-		// 		data: message,
-		// 	}
-		// } else {
+		if (message instanceof ArrayBuffer || ArrayBuffer.isView(message) || Array.isArray(message)) {
+			return {
+				contentType: 'application/octet-stream',
+				data: message,
+			}
+		}
 		const data = JSON.stringify(message, (_k, val) => {
 			// if this value changes, make sure to update it in:
 			// 1. ipc.js
@@ -36,7 +35,6 @@
 			contentType: 'application/json',
 			data,
 		}
-		// }
 	}
 
 	/**
@@ -55,20 +53,15 @@
 		if (!customProtocolIpcFailed) {
 			const { contentType, data } = processIpcMessage(payload)
 
-			// Headers can only contain ascii characters, so encoding is needed,
-			// and since tauri already depends on percent-encoding rust crate, we use `encodeURI` here for that reason
-			const invokeBody = encodeURI(data)
-
 			const headers = new Headers((options && options.headers) || {})
 			headers.set('Content-Type', contentType)
 			headers.set('Tauri-Callback', callback)
 			headers.set('Tauri-Error', error)
 			headers.set('Tauri-Invoke-Key', __TAURI_INVOKE_KEY__)
-			headers.set('Tauri-VersoRuntime-Invoke-Body', invokeBody)
 
 			fetch(window.__TAURI_INTERNALS__.convertFileSrc(cmd, 'ipc'), {
 				method: 'POST',
-				// body: data,
+				body: data,
 				headers,
 			})
 				.then((response) => {
@@ -79,6 +72,8 @@
 							return response.json().then((r) => [callbackId, r])
 						case 'text/plain':
 							return response.text().then((r) => [callbackId, r])
+						// e.g. `tauri::ipc::Response`, passed to the command's caller as an `ArrayBuffer`
+						case 'application/octet-stream':
 						default:
 							return response.arrayBuffer().then((r) => [callbackId, r])
 					}
@@ -53,32 +53,88 @@
 //!
 //! ## Tips
 //!
+//! ### Command arguments
+//!
+//! Tauri's types like [`tauri::WebviewWindow`] only default to the wry runtime,
+//! so a command taking `tauri::WebviewWindow` won't work with this runtime,
+//! use the aliases from this crate (or spell out the runtime) instead
+//!
+//! ```rust
+//! #[tauri::command]
+//! fn window_label(window: tauri_runtime_verso::WebviewWindow) -> String {
+//!     window.label().to_owned()
+//! }
+//!
+//! // Or be generic over the runtime
+//! #[tauri::command]
+//! fn window_title<R: tauri::Runtime>(window: tauri::WebviewWindow<R>) -> String {
+//!     window.title().unwrap_or_default()
+//! }
+//! ```
+//!
 //! ### Devtools
 //!
 //! Since Verso doesn't have a devtools built-in, you'll need to use the one from the Firefox, first put in this in your code
 //!
-//! ```rust
-//! // This will make the webviews created afters this open up a devtools server on this port,
+//! ```no_run
+//! // This will make the webviews open up a devtools server on this port,
 //! // setting it to 0 for a random port
-//! tauri_runtime_verso::set_verso_devtools_port(1234);
+//! tauri_runtime_verso::builder_with_config(
+//!     tauri_runtime_verso::VersoRuntimeConfig::new().devtools_port(1234),
+//! )
+//! .run(tauri::generate_context!())
+//! .unwrap();
 //! ```
 //!
 //! Then go to `about:debugging` in Firefox and connect to `localhost:1234` there
 //!
+//! You can also start the devtools server of a webview later with `open_devtools`,
+//! it uses this port or a random one if it's not set, and logs the port it's running on
+//!
 //! ## Cargo features
 //!
 //! - **macos-private-api**: Matching with Tauri's macos-private-api feature, required if you use that
 
+mod cache;
+mod config;
+mod error;
 mod event_loop_ext;
+mod input;
 mod monitor;
+mod protocol;
+mod proxy;
 mod runtime;
+mod session;
 mod utils;
 mod webview;
 mod window;
 
+pub use config::VersoRuntimeConfig;
+pub use error::VersoRuntimeError;
+pub use input::{KeyEvent, KeyEventKind, Modifiers, MouseButton, MouseEvent, MouseEventKind};
+pub use monitor::VideoMode;
+pub use protocol::{StreamBody, StreamingProtocolHandler};
+pub use proxy::{ProxyConfig, ProxyCredentials, ProxyEndpoint};
 pub use runtime::{EventProxy, RuntimeContext, VersoRuntime, VersoRuntimeHandle};
-pub use webview::VersoWebviewDispatcher;
-pub use window::{VersoWindowBuilder, VersoWindowDispatcher};
+pub use session::{SessionState, WindowState};
+pub use webview::{
+    CaptureElementError, Coordinates, EvalScriptError, FindOptions, FindResult, NavigateError,
+    VersoWebviewDispatcher,
+};
+pub use window::{
+    LoadEvent, NavigationOutcome, NewWindowResponse, VersoWindowBuilder, VersoWindowDispatcher,
+};
+
+/// [`tauri::App`] using the [`VersoRuntime`]
+pub type App = tauri::App<VersoRuntime>;
+/// [`tauri::AppHandle`] using the [`VersoRuntime`]
+pub type AppHandle = tauri::AppHandle<VersoRuntime>;
+/// [`tauri::Window`] using the [`VersoRuntime`]
+pub type Window = tauri::Window<VersoRuntime>;
+/// [`tauri::Webview`] using the [`VersoRuntime`]
+pub type Webview = tauri::Webview<VersoRuntime>;
+/// [`tauri::WebviewWindow`] using the [`VersoRuntime`]
+pub type WebviewWindow = tauri::WebviewWindow<VersoRuntime>;
 
 use std::{
     env::current_exe,
@@ -89,30 +145,65 @@ use std::{
 static VERSO_PATH: OnceLock<PathBuf> = OnceLock::new();
 
 /// Sets the Verso executable path to ues for the webviews,
-/// must be called before you create any webviews if you don't have the `externalBin` setup
-///
-/// ### Example:
+/// must be called before you create any webviews if you don't have the `externalBin` setup,
+/// this can also be a directory containing the `versoview(.exe)` executable
 ///
-/// ```
-/// fn main() {
-///     tauri_runtime_verso::set_verso_path("../verso/target/debug/versoview");
-///     tauri_runtime_verso::builder()
-///         .run(tauri::generate_context!())
-///         .unwrap();
-/// }
-/// ```
+/// This applies to every runtime in the process, use [`VersoRuntimeConfig::verso_path`] instead
+#[deprecated(note = "use `VersoRuntimeConfig::verso_path` with `builder_with_config` instead")]
 pub fn set_verso_path(path: impl Into<PathBuf>) {
     VERSO_PATH
         .set(path.into())
         .expect("Verso path is already set, you can't set it multiple times");
 }
 
-fn get_verso_path() -> &'static Path {
-    VERSO_PATH.get_or_init(|| {
-        relative_command_path("versoview").expect(
-            "Verso path not set! You need to call set_verso_path before creating any webviews!",
-        )
-    })
+/// Returns the path set by [`set_verso_path`], or looks for versoview next to the current executable,
+/// then at the `VERSOVIEW_PATH` environment variable, and then on `PATH`
+fn get_verso_path() -> std::result::Result<PathBuf, VersoRuntimeError> {
+    if let Some(path) = VERSO_PATH.get() {
+        return Ok(path.clone());
+    }
+    relative_command_path("versoview")
+        .or_else(|| std::env::var_os("VERSOVIEW_PATH").map(PathBuf::from))
+        .or_else(|| path_command_path("versoview"))
+        .ok_or(VersoRuntimeError::VersoviewNotFound)
+}
+
+/// Resolves the versoview executable from `path`,
+/// if `path` is a directory, looks for the `versoview(.exe)` executable inside of it
+fn resolve_verso_executable(path: &Path) -> std::result::Result<PathBuf, VersoRuntimeError> {
+    let executable = if path.is_dir() {
+        let extension = if cfg!(windows) { ".exe" } else { "" };
+        let executable = path.join(format!("versoview{extension}"));
+        if !executable.is_file() {
+            return Err(VersoRuntimeError::VersoviewNotInDirectory(path.to_owned()));
+        }
+        executable
+    } else if path.is_file() {
+        path.to_owned()
+    } else {
+        return Err(VersoRuntimeError::VersoPathNotFound(path.to_owned()));
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let is_executable = executable
+            .metadata()
+            .is_ok_and(|metadata| metadata.permissions().mode() & 0o111 != 0);
+        if !is_executable {
+            return Err(VersoRuntimeError::VersoviewNotExecutable(executable));
+        }
+    }
+
+    Ok(executable)
+}
+
+/// Looks for the executable `name` in the directories of the `PATH` environment variable
+fn path_command_path(name: &str) -> Option<PathBuf> {
+    let extension = if cfg!(windows) { ".exe" } else { "" };
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|directory| directory.join(format!("{name}{extension}")))
+        .find(|path| path.is_file())
 }
 
 fn relative_command_path(name: &str) -> Option<PathBuf> {
@@ -130,17 +221,10 @@ static VERSO_RESOURCES_DIRECTORY: Mutex<Option<PathBuf>> = Mutex::new(None);
 /// Sets the Verso resources directory to ues for the webviews,
 /// note this only affects webviews created after you set this
 ///
-/// ### Example:
-///
-/// ```
-/// fn main() {
-///     tauri_runtime_verso::set_verso_path("../verso/target/debug/versoview");
-///     tauri_runtime_verso::set_verso_resource_directory("../verso/resources");
-///     tauri_runtime_verso::builder()
-///         .run(tauri::generate_context!())
-///         .unwrap();
-/// }
-/// ```
+/// This applies to every runtime in the process, use [`VersoRuntimeConfig::resource_directory`] instead
+#[deprecated(
+    note = "use `VersoRuntimeConfig::resource_directory` with `builder_with_config` instead"
+)]
 pub fn set_verso_resource_directory(path: impl Into<PathBuf>) {
     VERSO_RESOURCES_DIRECTORY
         .lock()
@@ -159,8 +243,6 @@ fn get_verso_resource_directory() -> Option<PathBuf> {
 ///
 /// ```
 /// fn main() {
-///     tauri_runtime_verso::set_verso_path("../verso/target/debug/versoview");
-///     tauri_runtime_verso::set_verso_resource_directory("../verso/resources");
 ///     tauri::Builder::<tauri_runtime_verso::VersoRuntime>::new()
 ///         .invoke_system(tauri_runtime_verso::INVOKE_SYSTEM_SCRIPTS.to_owned())
 ///         .run(tauri::generate_context!())
@@ -177,6 +259,9 @@ static DEV_TOOLS_PORT: Mutex<Option<u16>> = Mutex::new(None);
 /// Since Verso doesn't have devtools built-in,
 /// you need to use the one from Firefox from the `about:debugging` page,
 /// this setting allows you to let verso open a port for it
+///
+/// This applies to every runtime in the process, use [`VersoRuntimeConfig::devtools_port`] instead
+#[deprecated(note = "use `VersoRuntimeConfig::devtools_port` with `builder_with_config` instead")]
 pub fn set_verso_devtools_port(port: u16) {
     DEV_TOOLS_PORT.lock().unwrap().replace(port);
 }
@@ -200,3 +285,104 @@ fn get_verso_devtools_port() -> Option<u16> {
 pub fn builder() -> tauri::Builder<VersoRuntime> {
     tauri::Builder::new().invoke_system(INVOKE_SYSTEM_SCRIPTS)
 }
+
+/// Creates a new [`tauri::Builder`] using the [`VersoRuntime`] like [`builder`],
+/// with the configuration for the runtime it creates,
+/// the configuration is carried by the returned builder, so each builder can have its own
+///
+/// [`tauri::Builder::build`] fails with a [`VersoRuntimeError::Config`] if the config can't be applied to the runtime,
+/// use [`VersoRuntime::new_with_config`] instead if you create the runtime yourself
+///
+/// ### Example:
+///
+/// ```no_run
+/// fn main() {
+///     let config = tauri_runtime_verso::VersoRuntimeConfig::new()
+///         .verso_path("../verso/target/debug/versoview")
+///         .resource_directory("../verso/resources");
+///     tauri_runtime_verso::builder_with_config(config)
+///         .run(tauri::generate_context!())
+///         .unwrap();
+/// }
+/// ```
+pub fn builder_with_config(config: VersoRuntimeConfig) -> tauri::Builder<VersoRuntime> {
+    // `tauri::Builder::build` creates the runtime with `Runtime::new` and has no way to pass the config to it,
+    // but it sets up the plugins right after on the same thread, before any window is created,
+    // and this is the first plugin on the builder
+    builder().plugin(
+        tauri::plugin::Builder::new("verso-runtime-config")
+            .setup(move |_app, _api| {
+                runtime::set_runtime_config(config)?;
+                Ok(())
+            })
+            .build(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an empty directory for a test, removing what an earlier run left there
+    fn test_directory(name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("tauri-runtime-verso-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    fn create_versoview(directory: &Path) -> PathBuf {
+        let extension = if cfg!(windows) { ".exe" } else { "" };
+        let executable = directory.join(format!("versoview{extension}"));
+        std::fs::write(&executable, "").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&executable, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        executable
+    }
+
+    #[test]
+    fn directory_resolves_to_the_versoview_inside() {
+        let directory = test_directory("directory");
+        let executable = create_versoview(&directory);
+        assert_eq!(resolve_verso_executable(&directory).unwrap(), executable);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn directory_without_versoview_is_rejected() {
+        let directory = test_directory("empty-directory");
+        assert!(matches!(
+            resolve_verso_executable(&directory),
+            Err(VersoRuntimeError::VersoviewNotInDirectory(path)) if path == directory
+        ));
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn missing_path_is_rejected() {
+        let path = test_directory("missing").join("versoview");
+        assert!(matches!(
+            resolve_verso_executable(&path),
+            Err(VersoRuntimeError::VersoPathNotFound(missing)) if missing == path
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_executable_file_is_rejected() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let directory = test_directory("not-executable");
+        let executable = create_versoview(&directory);
+        std::fs::set_permissions(&executable, std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(matches!(
+            resolve_verso_executable(&executable),
+            Err(VersoRuntimeError::VersoviewNotExecutable(path)) if path == executable
+        ));
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -4,7 +4,7 @@
 
 // This is copied from https://github.com/tauri-apps/tauri/tree/tauri-v2.6.0/crates/tauri-runtime-wry/src/monitor
 
-use tauri_runtime::dpi::PhysicalRect;
+use tauri_runtime::dpi::{PhysicalRect, PhysicalSize};
 
 #[cfg(any(
     target_os = "linux",
@@ -27,3 +27,27 @@ pub trait MonitorExt {
     /// - **Android / iOS**: Unsupported.
     fn work_area(&self) -> PhysicalRect<i32, u32>;
 }
+
+/// A video mode supported by a monitor,
+/// see [`VersoRuntimeHandle::monitor_video_modes`](crate::VersoRuntimeHandle::monitor_video_modes)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VideoMode {
+    /// The resolution of this video mode
+    pub size: PhysicalSize<u32>,
+    /// The bit depth of this video mode, e.g. 24 for 8 bits per color channel
+    pub bit_depth: u16,
+    /// The refresh rate of this video mode in hertz
+    ///
+    /// Tao only reports whole hertz, e.g. a 59.94 Hz mode is reported as 59 or 60 depending on the platform
+    pub refresh_rate: u16,
+}
+
+impl From<tao::monitor::VideoMode> for VideoMode {
+    fn from(video_mode: tao::monitor::VideoMode) -> Self {
+        Self {
+            size: video_mode.size(),
+            bit_depth: video_mode.bit_depth(),
+            refresh_rate: video_mode.refresh_rate(),
+        }
+    }
+}
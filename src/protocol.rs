@@ -0,0 +1,268 @@
+//! Streaming custom protocols, for serving large files (e.g. videos) without loading them into memory,
+//! see [`VersoRuntimeConfig::streaming_protocol`](crate::VersoRuntimeConfig::streaming_protocol)
+
+use http::{HeaderValue, Request, Response, StatusCode, header};
+
+use std::{
+    io::{self, Read, Seek, SeekFrom},
+    sync::{
+        Arc, Mutex, OnceLock,
+        mpsc::{Sender, channel},
+    },
+    thread,
+};
+
+/// A response body that can be read from any position
+pub trait StreamBody: Read + Seek + Send {}
+
+impl<T: Read + Seek + Send> StreamBody for T {}
+
+pub type StreamingProtocolHandler =
+    dyn Fn(&str, &Request<Vec<u8>>) -> Response<Box<dyn StreamBody>> + Send + Sync;
+
+/// The max size of the response body read into memory at once, larger bodies are served in chunks of this size,
+/// the webview will request the rest with more range requests when it needs them
+const MAX_RANGE_CHUNK_SIZE: u64 = 4 * 1024 * 1024;
+
+/// The number of threads the streaming protocol handlers run on,
+/// the requests made while they're all busy wait for one to be free
+const STREAMING_WORKERS: usize = 4;
+
+type Task = Box<dyn FnOnce() + Send + 'static>;
+
+/// Runs the task on one of the [`STREAMING_WORKERS`] threads, which are started on first use,
+/// so a page making a lot of requests (e.g. seeking through a video) doesn't start a thread for each of them
+pub(crate) fn spawn_streaming_task(task: impl FnOnce() + Send + 'static) {
+    static WORKERS: OnceLock<Sender<Task>> = OnceLock::new();
+    let sender = WORKERS.get_or_init(|| {
+        let (sender, receiver) = channel::<Task>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for index in 0..STREAMING_WORKERS {
+            let receiver = receiver.clone();
+            thread::Builder::new()
+                .name(format!("streaming-protocol-{index}"))
+                .spawn(move || {
+                    loop {
+                        // Release the lock before running the task so the other workers can pick up the next ones
+                        let Ok(task) = receiver.lock().unwrap().recv() else {
+                            break;
+                        };
+                        task();
+                    }
+                })
+                .expect("Failed to start a streaming protocol worker thread");
+        }
+        sender
+    });
+    // The workers never exit, so this can't fail
+    let _ = sender.send(Box::new(task));
+}
+
+/// Reads the part of the response body requested by the `Range` header,
+/// responds with `206 Partial Content` for a valid range (only the first one is served if there're multiple ranges),
+/// `416 Range Not Satisfiable` for an invalid one,
+/// and reads the whole body if the response is not a `200 OK`
+///
+/// Without a `Range` header, the whole body is read if it's not larger than [`MAX_RANGE_CHUNK_SIZE`],
+/// or else only its first chunk is served with `206 Partial Content` as if `bytes=0-` was requested
+pub(crate) fn read_range(
+    request: &Request<Vec<u8>>,
+    response: Response<Box<dyn StreamBody>>,
+) -> io::Result<Response<Vec<u8>>> {
+    let (mut parts, mut body) = response.into_parts();
+    parts
+        .headers
+        .insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+
+    if parts.status != StatusCode::OK {
+        let mut buffer = Vec::new();
+        body.read_to_end(&mut buffer)?;
+        return Ok(Response::from_parts(parts, buffer));
+    }
+
+    let length = body.seek(SeekFrom::End(0))?;
+    let range = match request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|range| range.to_str().ok())
+    {
+        Some(range) => parse_range(range, length),
+        None if length > MAX_RANGE_CHUNK_SIZE => Some((0, MAX_RANGE_CHUNK_SIZE - 1)),
+        None => {
+            body.seek(SeekFrom::Start(0))?;
+            let mut buffer = Vec::with_capacity(length as usize);
+            body.read_to_end(&mut buffer)?;
+            return Ok(Response::from_parts(parts, buffer));
+        }
+    };
+    let Some((start, end)) = range else {
+        parts.status = StatusCode::RANGE_NOT_SATISFIABLE;
+        parts.headers.insert(
+            header::CONTENT_RANGE,
+            HeaderValue::from_str(&format!("bytes */{length}")).unwrap(),
+        );
+        return Ok(Response::from_parts(parts, Vec::new()));
+    };
+
+    body.seek(SeekFrom::Start(start))?;
+    let mut buffer = Vec::with_capacity((end - start + 1) as usize);
+    body.take(end - start + 1).read_to_end(&mut buffer)?;
+
+    parts.status = StatusCode::PARTIAL_CONTENT;
+    parts.headers.insert(
+        header::CONTENT_RANGE,
+        HeaderValue::from_str(&format!("bytes {start}-{end}/{length}")).unwrap(),
+    );
+    parts.headers.insert(
+        header::CONTENT_LENGTH,
+        HeaderValue::from_str(&buffer.len().to_string()).unwrap(),
+    );
+    Ok(Response::from_parts(parts, buffer))
+}
+
+/// Parses the first range of a `Range` header into the inclusive start and end positions,
+/// capped to [`MAX_RANGE_CHUNK_SIZE`] bytes, returns [`None`] if it's invalid or not satisfiable
+///
+/// The other ranges of a multiple ranges request (e.g. `bytes=0-99,200-299`) are ignored,
+/// the `Content-Range` of the response tells the webview which part it got
+fn parse_range(range: &str, length: u64) -> Option<(u64, u64)> {
+    let range = range.trim().strip_prefix("bytes=")?.split(',').next()?;
+    let (start, end) = range.split_once('-')?;
+    if length == 0 {
+        return None;
+    }
+    let (start, end) = match (start.trim(), end.trim()) {
+        // `bytes=-500`, the last 500 bytes
+        ("", suffix) => {
+            let suffix = suffix.parse::<u64>().ok()?.min(length);
+            if suffix == 0 {
+                return None;
+            }
+            (length - suffix, length - 1)
+        }
+        // `bytes=500-`
+        (start, "") => (start.parse::<u64>().ok()?, length - 1),
+        // `bytes=500-999`
+        (start, end) => (
+            start.parse::<u64>().ok()?,
+            end.parse::<u64>().ok()?.min(length - 1),
+        ),
+    };
+    let end = end.min(start.saturating_add(MAX_RANGE_CHUNK_SIZE - 1));
+    (start <= end).then_some((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_closed_range() {
+        assert_eq!(parse_range("bytes=0-99", 1000), Some((0, 99)));
+        assert_eq!(parse_range("bytes=500-999", 1000), Some((500, 999)));
+    }
+
+    #[test]
+    fn parse_suffix_range() {
+        assert_eq!(parse_range("bytes=-100", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=-2000", 1000), Some((0, 999)));
+        assert_eq!(parse_range("bytes=-0", 1000), None);
+    }
+
+    #[test]
+    fn parse_open_ended_range() {
+        assert_eq!(parse_range("bytes=100-", 1000), Some((100, 999)));
+        let length = 10 * MAX_RANGE_CHUNK_SIZE;
+        assert_eq!(
+            parse_range("bytes=0-", length),
+            Some((0, MAX_RANGE_CHUNK_SIZE - 1))
+        );
+    }
+
+    #[test]
+    fn closed_range_is_capped() {
+        let length = 10 * MAX_RANGE_CHUNK_SIZE;
+        assert_eq!(
+            parse_range(&format!("bytes=0-{}", length - 1), length),
+            Some((0, MAX_RANGE_CHUNK_SIZE - 1))
+        );
+        assert_eq!(
+            parse_range("bytes=0-18446744073709551615", length),
+            Some((0, MAX_RANGE_CHUNK_SIZE - 1))
+        );
+    }
+
+    #[test]
+    fn reversed_range_is_not_satisfiable() {
+        assert_eq!(parse_range("bytes=500-100", 1000), None);
+    }
+
+    #[test]
+    fn out_of_bounds_range() {
+        assert_eq!(parse_range("bytes=1000-", 1000), None);
+        assert_eq!(parse_range("bytes=2000-3000", 1000), None);
+        assert_eq!(parse_range("bytes=900-2000", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=0-0", 0), None);
+    }
+
+    #[test]
+    fn multiple_ranges_serve_the_first_one() {
+        assert_eq!(parse_range("bytes=0-99,200-299", 1000), Some((0, 99)));
+        assert_eq!(parse_range("bytes=-100, 0-10", 1000), Some((900, 999)));
+    }
+
+    fn response(length: usize) -> Response<Box<dyn StreamBody>> {
+        let body: Vec<u8> = (0..length).map(|index| index as u8).collect();
+        Response::new(Box::new(io::Cursor::new(body)))
+    }
+
+    #[test]
+    fn small_body_without_range_is_read_whole() {
+        let request = Request::new(Vec::new());
+        let response = read_range(&request, response(1000)).unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.body().len(), 1000);
+        assert!(response.headers().get(header::CONTENT_RANGE).is_none());
+    }
+
+    #[test]
+    fn large_body_without_range_is_chunked() {
+        let request = Request::new(Vec::new());
+        let length = MAX_RANGE_CHUNK_SIZE as usize + 1;
+        let response = read_range(&request, response(length)).unwrap();
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.body().len() as u64, MAX_RANGE_CHUNK_SIZE);
+        assert_eq!(
+            response.headers()[header::CONTENT_RANGE],
+            format!("bytes 0-{}/{length}", MAX_RANGE_CHUNK_SIZE - 1)
+        );
+    }
+
+    #[test]
+    fn streaming_tasks_run_on_the_workers() {
+        let (sender, receiver) = channel();
+        for index in 0..STREAMING_WORKERS * 2 {
+            let sender = sender.clone();
+            spawn_streaming_task(move || {
+                let name = thread::current().name().map(str::to_owned);
+                sender.send((index, name)).unwrap();
+            });
+        }
+        let mut indices: Vec<_> = (0..STREAMING_WORKERS * 2)
+            .map(|_| {
+                let (index, name) = receiver.recv().unwrap();
+                assert!(name.unwrap().starts_with("streaming-protocol-"));
+                index
+            })
+            .collect();
+        indices.sort();
+        assert_eq!(indices, (0..STREAMING_WORKERS * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn invalid_range() {
+        assert_eq!(parse_range("items=0-99", 1000), None);
+        assert_eq!(parse_range("bytes=abc", 1000), None);
+        assert_eq!(parse_range("bytes=a-b", 1000), None);
+    }
+}
@@ -11,9 +11,9 @@ use tao::{
 use tauri_runtime::{
     DeviceEventFilter, Error, EventLoopProxy, ExitRequestedEventAction, Result, RunEvent, Runtime,
     RuntimeHandle, RuntimeInitArgs, UserEvent, WindowEventId,
-    dpi::PhysicalPosition,
+    dpi::{PhysicalPosition, PhysicalSize},
     monitor::Monitor,
-    webview::{DetachedWebview, PendingWebview},
+    webview::{DetachedWebview, PageLoadEvent, PendingWebview},
     window::{
         DetachedWindow, DetachedWindowWebview, PendingWindow, RawWindow, WindowBuilder,
         WindowEvent, WindowId,
@@ -21,29 +21,64 @@ use tauri_runtime::{
 };
 use tauri_utils::Theme;
 use url::Url;
-use verso::CustomProtocolBuilder;
+use verso::{CustomProtocolBuilder, VersoviewController};
 
 use std::{
     borrow::Cow,
+    cell::RefCell,
     collections::HashMap,
     fmt::{self, Debug},
-    ops::Deref,
+    io::{self, BufRead, BufReader},
+    ops::{Deref, RangeInclusive},
+    path::PathBuf,
+    process::Stdio,
     sync::{
-        Arc, Mutex,
-        atomic::{AtomicU32, Ordering},
+        Arc, Mutex, OnceLock,
+        atomic::{AtomicBool, AtomicU32, Ordering},
         mpsc::channel,
     },
-    thread::{ThreadId, current as current_thread},
+    thread::{ThreadId, current as current_thread, sleep, spawn},
+    time::{Duration, Instant},
 };
 
+#[cfg(target_os = "macos")]
+use crate::utils::to_tao_activation_policy;
 use crate::{
+    ProxyConfig, VersoRuntimeConfig, VersoRuntimeError, VideoMode,
+    cache::{CacheLookup, ResponseCache},
     event_loop_ext::TaoEventLoopWindowTargetExt,
-    get_verso_path,
-    utils::{to_tao_theme, to_verso_theme},
-    webview::VersoWebviewDispatcher,
-    window::{VersoWindowDispatcher, Window},
+    get_verso_devtools_port, get_verso_path, get_verso_resource_directory,
+    protocol::{read_range, spawn_streaming_task},
+    resolve_verso_executable,
+    session::{SessionState, WindowState},
+    utils::{
+        clamp_to_monitors, css_injection_script, from_verso_drag_drop_event, from_verso_theme,
+        parse_version, system_theme, to_js_string, to_tao_theme, to_verso_theme,
+    },
+    webview::{LoadProgress, PendingEvents, UserScripts, VersoWebviewDispatcher},
+    window::{
+        FullscreenState, LoadEvent, NavigationOutcome, NewWindowResponse, RestartBackoff,
+        SpawnVersoview, VersoWindowDispatcher, Window,
+    },
 };
 
+/// The resources directory next to the versoview executable, see [`RuntimeContext::verso_resource_directory`]
+const VERSOVIEW_RESOURCES_DIRECTORY_NAME: &str = "versoview-resources";
+/// How often to check for removed monitors, see [`VersoRuntimeConfig::reposition_on_monitor_removed`]
+const MONITOR_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How often to check if the system theme changed, see [`RuntimeContext::handle_system_theme_changed`]
+const SYSTEM_THEME_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// See [`crate::VersoWindowBuilder::close_requested_bridge`]
+const CLOSE_REQUESTED_SCRIPT: &str = include_str!("./close-requested-script.js");
+/// How long to wait for the page to allow or cancel closing the window
+const CLOSE_REQUESTED_TIMEOUT: Duration = Duration::from_secs(10);
+const CLOSE_REQUESTED_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The versoview versions the IPC of this crate works with, as `(major, minor, patch)`,
+/// versions before `0.1.0` have breaking changes in every release
+const SUPPORTED_VERSOVIEW_VERSIONS: RangeInclusive<(u64, u64, u64)> = (0, 0, 9)..=(0, 0, 9);
+
 type Task = Box<dyn FnOnce() + Send + 'static>;
 type TaskWithEventLoop<T> = Box<dyn FnOnce(&TaoEventLoopWindowTarget<Message<T>>) + Send + 'static>;
 
@@ -52,7 +87,20 @@ pub enum Message<T: UserEvent> {
     /// Run task with the [`EventLoopWindowTarget`](TaoEventLoopWindowTarget)
     TaskWithEventLoop(TaskWithEventLoop<T>),
     CloseWindow(WindowId),
+    /// Continue the close request of this window after checking for an unload prompt off the main thread,
+    /// see [`RuntimeContext::check_unload_prompt`]
+    CloseWindowChecked(WindowId),
     DestroyWindow(WindowId),
+    /// Notify the listeners of this window and the app about a [`WindowEvent`]
+    WindowEvent(WindowId, WindowEvent),
+    /// The versoview process of this window exited
+    VersoviewExited(WindowId),
+    /// Spawn the versoview process of this window again after it exited unexpectedly
+    RestartVersoview(WindowId),
+    /// Check if the monitors changed and move the windows on removed monitors
+    CheckMonitors,
+    /// Check if the system theme changed and apply it to the windows following it
+    CheckSystemTheme,
     RequestExit(i32),
     UserEvent(T),
 }
@@ -83,6 +131,9 @@ unsafe impl<T: UserEvent> Sync for DispatcherMainThreadContext<T> {}
 pub struct RuntimeContext<T: UserEvent> {
     windows: Arc<Mutex<HashMap<WindowId, Window>>>,
     prefered_theme: Arc<Mutex<Option<Theme>>>,
+    /// The last known system theme, used when there's no [`Self::prefered_theme`],
+    /// the outer `None` means it's not queried yet, see [`Self::system_theme`]
+    system_theme: Arc<Mutex<Option<Option<Theme>>>>,
     event_proxy: TaoEventLoopProxy<Message<T>>,
     // This must only be used on main thread
     main_thread: DispatcherMainThreadContext<T>,
@@ -91,9 +142,90 @@ pub struct RuntimeContext<T: UserEvent> {
     next_webview_id: Arc<AtomicU32>,
     next_window_event_id: Arc<AtomicU32>,
     next_webview_event_id: Arc<AtomicU32>,
+    /// The last known monitors, so background threads don't need to wait on the main thread,
+    /// queried when the event loop is created
+    monitors: Arc<Mutex<Vec<Monitor>>>,
+    /// If a refresh of [`Self::monitors`] is queued on the main thread, so background threads only queue one at a time
+    refreshing_monitors: Arc<AtomicBool>,
+    /// The app id tauri sets from `app > enableGTKAppId`, only used on Linux,
+    /// [`VersoRuntimeConfig::app_id`] takes precedence over it
+    app_id: Option<String>,
+    /// Set when the runtime is created with [`VersoRuntime::new_with_config`],
+    /// or by [`crate::builder_with_config`] right after it's created, see [`set_runtime_config`]
+    config: Arc<OnceLock<VersoRuntimeConfig>>,
+}
+
+thread_local! {
+    /// The config of the runtime [`tauri::Builder::build`] is building on this thread,
+    /// set for the runtimes created without a config, see [`set_runtime_config`]
+    static PENDING_RUNTIME_CONFIG: RefCell<Option<Arc<OnceLock<VersoRuntimeConfig>>>> =
+        const { RefCell::new(None) };
+}
+
+/// Sets the config of the runtime created on this thread by [`tauri::Builder::build`],
+/// which creates the runtime and then sets up the plugins on the same thread before creating any window,
+/// so this is called from the plugin added by [`crate::builder_with_config`]
+///
+/// Returns an error if there's no runtime waiting for its config,
+/// or if the runtime already used its default config, instead of ignoring `config`
+pub(crate) fn set_runtime_config(
+    config: VersoRuntimeConfig,
+) -> std::result::Result<(), VersoRuntimeError> {
+    let runtime_config = PENDING_RUNTIME_CONFIG
+        .with_borrow_mut(Option::take)
+        .ok_or_else(|| {
+            VersoRuntimeError::Config(
+                "there's no runtime being built on this thread to apply it to".to_owned(),
+            )
+        })?;
+    runtime_config.set(config).map_err(|_| {
+        VersoRuntimeError::Config("the runtime already started with the default config".to_owned())
+    })
 }
 
 impl<T: UserEvent> RuntimeContext<T> {
+    /// The config from [`VersoRuntime::new_with_config`] or [`crate::builder_with_config`],
+    /// the default config once it's used without one
+    pub(crate) fn config(&self) -> &VersoRuntimeConfig {
+        self.config.get_or_init(VersoRuntimeConfig::default)
+    }
+
+    /// The Verso path from [`VersoRuntimeConfig::verso_path`], or the process-wide one if it's not set
+    pub(crate) fn verso_path(&self) -> std::result::Result<PathBuf, VersoRuntimeError> {
+        match &self.config().verso_path {
+            Some(path) => Ok(path.clone()),
+            None => get_verso_path(),
+        }
+    }
+
+    /// The resources directory from [`VersoRuntimeConfig::resource_directory`], or the process-wide one if it's not set,
+    /// falls back to the `versoview-resources` directory next to the versoview executable if there's one,
+    /// which is where `tauri-runtime-verso-build` puts the resources
+    pub(crate) fn verso_resource_directory(&self) -> Option<PathBuf> {
+        self.config()
+            .resource_directory
+            .clone()
+            .or_else(get_verso_resource_directory)
+            .or_else(|| {
+                let resource_directory = self
+                    .verso_path()
+                    .ok()?
+                    .parent()?
+                    .join(VERSOVIEW_RESOURCES_DIRECTORY_NAME);
+                resource_directory.is_dir().then_some(resource_directory)
+            })
+    }
+
+    /// The app id for the windows, [`VersoRuntimeConfig::app_id`] or the one tauri sets if it's not set
+    pub(crate) fn window_app_id(&self) -> Option<&str> {
+        self.config().app_id.as_deref().or(self.app_id.as_deref())
+    }
+
+    /// The devtools port from [`VersoRuntimeConfig::devtools_port`], or the process-wide one if it's not set
+    pub(crate) fn verso_devtools_port(&self) -> Option<u16> {
+        self.config().devtools_port.or_else(get_verso_devtools_port)
+    }
+
     pub fn send_message(&self, message: Message<T>) -> Result<()> {
         if current_thread().id() == self.main_thread_id {
             match message {
@@ -135,6 +267,105 @@ impl<T: UserEvent> RuntimeContext<T> {
             .map_err(|_| tauri_runtime::Error::FailedToReceiveMessage)
     }
 
+    /// Returns the available monitors,
+    /// called from the main thread, this queries the monitors directly,
+    /// otherwise this returns the last known monitors immediately without waiting on the main thread
+    /// and refreshes them on the main thread in the background,
+    /// use [`Self::refresh_available_monitors`] to wait for the latest monitors instead
+    pub fn available_monitors(&self) -> Result<Vec<Monitor>> {
+        if current_thread().id() == self.main_thread_id {
+            return self.refresh_available_monitors();
+        }
+        let monitors = self.monitors.lock().unwrap().clone();
+        // There's no need to queue another refresh if the last one isn't handled yet
+        if !self.refreshing_monitors.swap(true, Ordering::AcqRel) {
+            let cache = self.monitors.clone();
+            let refreshing_monitors = self.refreshing_monitors.clone();
+            let sent = self.send_message(Message::TaskWithEventLoop(Box::new(move |e| {
+                *cache.lock().unwrap() = e.tauri_available_monitors();
+                refreshing_monitors.store(false, Ordering::Release);
+            })));
+            if let Err(error) = sent {
+                self.refreshing_monitors.store(false, Ordering::Release);
+                return Err(error);
+            }
+        }
+        Ok(monitors)
+    }
+
+    /// Queries the available monitors on the main thread and updates the cached monitors,
+    /// this blocks until the main thread handles it
+    pub fn refresh_available_monitors(&self) -> Result<Vec<Monitor>> {
+        let monitors = self.run_on_main_thread_with_event_loop(|e| e.tauri_available_monitors())?;
+        *self.monitors.lock().unwrap() = monitors.clone();
+        Ok(monitors)
+    }
+
+    /// Returns the theme for the windows without an explicit theme,
+    /// the one set through [`RuntimeHandle::set_theme`] or the system theme if that's `None`
+    pub(crate) fn default_theme(&self) -> Option<Theme> {
+        let prefered_theme = *self.prefered_theme.lock().unwrap();
+        prefered_theme.or_else(|| self.system_theme())
+    }
+
+    /// Returns the last known system theme, querying it on first use,
+    /// `None` if it can't be detected (e.g. there's no display server),
+    /// in which case the windows use versoview's default theme
+    fn system_theme(&self) -> Option<Theme> {
+        if let Some(theme) = *self.system_theme.lock().unwrap() {
+            return theme;
+        }
+        let theme = self.query_system_theme();
+        *self.system_theme.lock().unwrap() = Some(theme);
+        theme
+    }
+
+    /// Queries the system theme, this goes through the main thread on Linux since GTK is only usable there
+    fn query_system_theme(&self) -> Option<Theme> {
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        {
+            self.run_on_main_thread_with_event_loop(|_| system_theme())
+                .ok()
+                .flatten()
+        }
+        #[cfg(not(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        )))]
+        {
+            system_theme()
+        }
+    }
+
+    /// Applies [`Self::default_theme`] to the webviews of the windows without an explicit theme
+    fn apply_default_theme(&self) {
+        let default_theme = self.default_theme();
+        for window in self.windows.lock().unwrap().values() {
+            let theme = window.explicit_theme.lock().unwrap().or(default_theme);
+            if let Err(error) = window
+                .webview
+                .lock()
+                .unwrap()
+                .set_theme(theme.map(to_verso_theme))
+            {
+                log::error!("Failed to set the theme for webview: {error}");
+            }
+        }
+    }
+
+    pub fn has_window(&self, id: WindowId) -> bool {
+        self.windows.lock().unwrap().contains_key(&id)
+    }
+
     pub fn next_window_id(&self) -> WindowId {
         self.next_window_id.fetch_add(1, Ordering::Relaxed).into()
     }
@@ -154,7 +385,8 @@ impl<T: UserEvent> RuntimeContext<T> {
     /// `after_window_creation` not supported
     ///
     /// Only creating the window with a webview is supported,
-    /// will return [`tauri_runtime::Error::CreateWindow`] if there is no [`PendingWindow::webview`]
+    /// will return [`tauri_runtime::Error::CreateWindow`] if there is no [`PendingWindow::webview`],
+    /// and [`tauri_runtime::Error::CreateWebview`] with a [`VersoRuntimeError`] if versoview fails to start
     pub fn create_window<
         R: Runtime<
                 T,
@@ -174,139 +406,625 @@ impl<T: UserEvent> RuntimeContext<T> {
 
         let window_id = self.next_window_id();
         let webview_id = self.next_webview_id();
+        // Tauri looks up the webview of a webview window by this label,
+        // which is the same as the window label for the windows created through `WebviewWindowBuilder`
+        let detached_webview_label = pending_webview.label.clone();
+
+        let verso_path = self
+            .verso_path()
+            .and_then(|path| resolve_verso_executable(&path))
+            .map_err(|error| {
+                log::error!("Failed to create window ({label}): {error}");
+                error
+            })?;
 
         let mut window_builder = pending.window_builder;
 
+        if let Some(resource_directory) = self.verso_resource_directory() {
+            window_builder.verso_builder = window_builder
+                .verso_builder
+                .resources_directory(resource_directory);
+        }
+        if self.config().headless {
+            window_builder.verso_builder = window_builder.verso_builder.headless(true);
+        }
+        if let Some(devtools_port) = self.verso_devtools_port() {
+            window_builder.verso_builder =
+                window_builder.verso_builder.devtools_port(devtools_port);
+        }
+
+        if window_builder.has_invalid_parent {
+            log::error!("The parent window of window ({label}) is not a valid window handle");
+            return Err(tauri_runtime::Error::CreateWindow);
+        }
+
+        let explicit_theme = Arc::new(Mutex::new(window_builder.get_theme()));
         if window_builder.get_theme().is_none() {
-            window_builder = window_builder.theme(*self.prefered_theme.lock().unwrap());
+            window_builder = window_builder.theme(self.default_theme());
+        }
+
+        // The windows live in the versoview processes,
+        // so the app id needs to be set on them for the desktop environment to match the `.desktop` file
+        if let Some(app_id) = self.window_app_id() {
+            window_builder.verso_builder = window_builder.verso_builder.app_id(app_id.to_owned());
+        }
+
+        if let Some(user_agent) = &pending_webview.webview_attributes.user_agent {
+            window_builder = window_builder.user_agent(user_agent);
+        }
+
+        // The profile (cookies, storage, cache, etc.) lives in the versoview process,
+        // with a data directory, the content settings (zoom, granted permissions, etc.) are kept in there too,
+        // so they're restored when a window is opened with the same data directory again
+        if let Some(data_directory) = &pending_webview.webview_attributes.data_directory {
+            window_builder.verso_builder = window_builder
+                .verso_builder
+                .data_directory(data_directory.clone())
+                .persist_content_settings(true);
+        }
+
+        window_builder = window_builder
+            .zoom_hotkeys_enabled(pending_webview.webview_attributes.zoom_hotkeys_enabled);
+
+        #[cfg(windows)]
+        if !pending_webview.webview_attributes.drag_drop_handler_enabled {
+            window_builder = window_builder.drag_and_drop(false);
         }
 
-        let webview = window_builder
+        if let Some(proxy_url) = &pending_webview.webview_attributes.proxy_url {
+            match ProxyConfig::from_url(proxy_url) {
+                Some(proxy) => window_builder = window_builder.proxy(proxy),
+                None => log::error!(
+                    "Unsupported proxy URL {proxy_url} for window ({label}), only http and socks5 are supported"
+                ),
+            }
+        }
+
+        // Tauri sets this in its own initialization scripts too,
+        // this is a fallback for `getCurrentWindow` and `getCurrentWebviewWindow` in case they run before it
+        let metadata_script = format!(
+            r#"window.__TAURI_INTERNALS__ = window.__TAURI_INTERNALS__ || {{}};
+if (!window.__TAURI_INTERNALS__.metadata) {{
+  window.__TAURI_INTERNALS__.metadata = {{
+    currentWindow: {{ label: {window_label} }},
+    currentWebview: {{ windowLabel: {window_label}, label: {webview_label} }},
+  }};
+}}"#,
+            window_label = to_js_string(&label),
+            webview_label = to_js_string(&detached_webview_label),
+        );
+
+        let streaming_protocols = self.config().streaming_protocols.clone();
+        let window_level = window_builder.window_level();
+        let mut verso_builder = window_builder
             .verso_builder
+            .window_level(window_level)
             .user_scripts(
-                pending_webview
-                    .webview_attributes
-                    .initialization_scripts
-                    .into_iter()
-                    .map(|script| script.script),
+                std::iter::once(metadata_script)
+                    .chain(
+                        pending_webview
+                            .webview_attributes
+                            .initialization_scripts
+                            .into_iter()
+                            .map(|script| script.script),
+                    )
+                    .chain(
+                        window_builder
+                            .css
+                            .iter()
+                            .map(|css| css_injection_script(css)),
+                    )
+                    .chain(
+                        window_builder
+                            .close_requested_bridge
+                            .then(|| CLOSE_REQUESTED_SCRIPT.to_owned()),
+                    ),
             )
             .custom_protocols(
                 pending_webview
                     .uri_scheme_protocols
                     .keys()
+                    .chain(streaming_protocols.iter().map(|(scheme, _)| scheme))
                     .map(CustomProtocolBuilder::new),
-            )
-            .build(get_verso_path(), Url::parse(&pending_webview.url).unwrap());
+            );
 
-        let webview_label = label.clone();
-        let sender = self.event_proxy.clone();
+        let capture_output = window_builder
+            .capture_output
+            .unwrap_or(self.config().capture_output);
+        #[cfg(windows)]
+        let kill_on_parent_exit = window_builder.kill_on_parent_exit;
+        let new_window_handler = window_builder.new_window_handler;
+        let navigation_finished_handler = window_builder.navigation_finished_handler;
+        let load_event_handler = window_builder.load_event_handler;
+        let mixed_content_blocked_handler = window_builder.mixed_content_blocked_handler;
+        let fullscreen = Arc::new(Mutex::new(FullscreenState {
+            fullscreen: window_builder.fullscreen,
+            ..Default::default()
+        }));
+        if capture_output {
+            verso_builder = verso_builder.stdout(Stdio::piped()).stderr(Stdio::piped());
+        }
+
+        let url = Url::parse(&pending_webview.url).unwrap();
+        let current_url = Arc::new(Mutex::new(url.clone()));
+        let use_https_scheme = pending_webview.webview_attributes.use_https_scheme;
         let uri_scheme_protocols: HashMap<_, _> = pending_webview
             .uri_scheme_protocols
             .into_iter()
             .map(|(key, value)| (key, Arc::new(value)))
             .collect();
-        webview
-            .on_web_resource_requested(move |mut request, response_fn| {
-                // dbg!(&request);
-                // TODO: Servo's EmbedderMsg::WebResourceRequested message is sent too early
-                // that it doesn't include Origin header, so I hard coded this for now
-                if !request.headers().contains_key("Origin") {
-                    #[cfg(windows)]
-                    let uri = {
-                        let scheme = if pending_webview.webview_attributes.use_https_scheme {
-                            "https"
-                        } else {
-                            "http"
-                        };
-                        format!("{scheme}://tauri.localhost")
-                    };
-                    #[cfg(not(windows))]
-                    let uri = "tauri://localhost";
-                    request.headers_mut().insert("Origin", uri.parse().unwrap());
+        let navigation_handler = pending_webview
+            .navigation_handler
+            .map(|handler| Arc::new(Mutex::new(handler)));
+        let on_page_load_handler = pending_webview
+            .on_page_load_handler
+            .map(|handler| Arc::new(Mutex::new(handler)));
+        let drag_drop_handler_enabled =
+            pending_webview.webview_attributes.drag_drop_handler_enabled;
+
+        // The custom protocol handlers are called with the webview label
+        let webview_label = detached_webview_label.clone();
+        let webview_current_url = current_url.clone();
+        let load_progress = Arc::new(Mutex::new(LoadProgress::default()));
+        let webview_load_progress = load_progress.clone();
+        let pending_events = Arc::new(Mutex::new(PendingEvents::default()));
+        let webview_pending_events = pending_events.clone();
+        let process_id = Arc::new(AtomicU32::new(0));
+        let webview_process_id = process_id.clone();
+        let zoom = Arc::new(Mutex::new(1.0));
+        let webview_zoom = zoom.clone();
+        let focused = Arc::new(AtomicBool::new(false));
+        let webview_focused = focused.clone();
+        let user_scripts = Arc::new(Mutex::new(UserScripts::new()));
+        let webview_user_scripts = user_scripts.clone();
+        let windows = self.windows.clone();
+        let cached_protocols = self.config().cached_protocols.clone();
+        // Shared across restarts of the versoview process
+        let response_cache = Arc::new(Mutex::new(ResponseCache::default()));
+        let event_proxy = self.event_proxy.clone();
+        // Keep everything needed to spawn the versoview around,
+        // so we can spawn it again if the process exits unexpectedly
+        let spawn_versoview: Arc<SpawnVersoview> = Arc::new(move |url: Url| {
+            let mut webview = verso_builder.clone().build(&verso_path, url);
+            webview_process_id.store(webview.process_id(), Ordering::Relaxed);
+
+            #[cfg(windows)]
+            if kill_on_parent_exit {
+                if let Err(error) = crate::utils::kill_on_parent_exit(webview.process_id()) {
+                    log::error!(
+                        "Failed to put the versoview process in a job object, it will be left running if the app crashes ({webview_label}): {error}"
+                    );
                 }
-                for (scheme, handler) in &uri_scheme_protocols {
-                    // Since servo doesn't support body in its EmbedderMsg::WebResourceRequested yet,
-                    // we use a header instead for now
-                    if scheme == "ipc" {
-                        if let Some(data) = request
-                            .headers_mut()
-                            .remove("Tauri-VersoRuntime-Invoke-Body")
-                        {
-                            if let Ok(body) =
-                                percent_encoding::percent_decode(data.as_bytes()).decode_utf8()
-                            {
-                                *request.body_mut() = body.as_bytes().to_vec();
-                            } else {
-                                log::error!("IPC invoke body header is not a valid UTF-8 string");
-                            }
+            }
+
+            if capture_output {
+                forward_versoview_output(&mut webview, &webview_label);
+            }
+
+            check_versoview_version(&webview)?;
+
+            // Add back the user scripts added after creation when restarting
+            for (name, script) in webview_user_scripts.lock().unwrap().iter() {
+                webview
+                    .add_user_script(name.clone(), script.clone())
+                    .map_err(|error| VersoRuntimeError::Spawn(error.to_string()))?;
+            }
+
+            let label = webview_label.clone();
+            let sender = event_proxy.clone();
+            let uri_scheme_protocols = uri_scheme_protocols.clone();
+            let streaming_protocols = streaming_protocols.clone();
+            let cached_protocols = cached_protocols.clone();
+            let response_cache = response_cache.clone();
+            webview
+                .on_web_resource_requested(move |mut request, response_fn| {
+                    // dbg!(&request);
+                    for (scheme, handler) in &streaming_protocols {
+                        if match_custom_protocol_uri(&mut request, scheme, use_https_scheme) {
+                            // Run the handler on a worker thread since reading the body can take a while
+                            let handler = handler.0.clone();
+                            let label = label.clone();
+                            let responder = WebResourceResponder::new(response_fn);
+                            spawn_streaming_task(move || {
+                                let response = handler(&label, &request);
+                                match read_range(&request, response) {
+                                    Ok(response) => responder.respond(response),
+                                    Err(error) => log::error!(
+                                        "Failed to read the response body for {}: {error}",
+                                        request.uri()
+                                    ),
+                                }
+                            });
+                            return;
                         }
                     }
-                    #[cfg(windows)]
-                    let (uri, http_or_https) = (
-                        request.uri().to_string(),
-                        if pending_webview.webview_attributes.use_https_scheme {
-                            "https"
-                        } else {
-                            "http"
-                        },
-                    );
-                    #[cfg(windows)]
-                    let is_custom_protocol_uri = is_work_around_uri(&uri, http_or_https, scheme);
-                    #[cfg(not(windows))]
-                    let is_custom_protocol_uri = request.uri().scheme_str() == Some(scheme);
-                    if is_custom_protocol_uri {
-                        #[cfg(windows)]
-                        {
-                            if let Ok(reverted) =
-                                revert_custom_protocol_work_around(&uri, http_or_https, scheme)
-                            {
-                                *request.uri_mut() = reverted
-                            } else {
-                                log::error!("Can't revert the URI work around on: {uri}")
-                            };
+                    for (scheme, handler) in &uri_scheme_protocols {
+                        if match_custom_protocol_uri(&mut request, scheme, use_https_scheme) {
+                            // Servo's EmbedderMsg::WebResourceRequested message can be sent too early
+                            // that it doesn't include the Origin header, Tauri needs it for the
+                            // custom protocols (IPC especially), so we fill in the app's origin here,
+                            // other requests are left untouched to not mess up CORS
+                            if !request.headers().contains_key("Origin") {
+                                #[cfg(windows)]
+                                let origin = {
+                                    let scheme = if use_https_scheme { "https" } else { "http" };
+                                    format!("{scheme}://tauri.localhost")
+                                };
+                                #[cfg(not(windows))]
+                                let origin = "tauri://localhost";
+                                request
+                                    .headers_mut()
+                                    .insert("Origin", origin.parse().unwrap());
+                            }
+                            let cache = cached_protocols
+                                .contains(scheme)
+                                .then(|| ResponseCache::key(&request))
+                                .flatten()
+                                .map(|key| (key, response_cache.clone()));
+                            let mut retry_request = None;
+                            if let Some((key, cache)) = &cache {
+                                match cache.lock().unwrap().get(key, &mut request) {
+                                    CacheLookup::Fresh(response) => {
+                                        response_fn(Some(response));
+                                        return;
+                                    }
+                                    CacheLookup::Revalidate(request) => {
+                                        retry_request = Some(request);
+                                    }
+                                    CacheLookup::Miss => {}
+                                }
+                            }
+                            // Run the handler on main thread, this is needed because Tauri expects this,
+                            // the handler can hold on to the responder and respond later from another thread
+                            // (e.g. `register_asynchronous_uri_scheme_protocol`) so it doesn't block the main thread
+                            let handler = handler.clone();
+                            let label = label.clone();
+                            let responder = WebResourceResponder::new(response_fn);
+                            let retry_sender = sender.clone();
+                            let _ = sender.send_event(Message::Task(Box::new(move || {
+                                let retry_handler = handler.clone();
+                                let retry_label = label.clone();
+                                handler(
+                                    &label,
+                                    request,
+                                    Box::new(move |response| {
+                                        let mut response = response.map(Cow::into_owned);
+                                        if let Some((key, cache)) = cache {
+                                            let mut response_cache = cache.lock().unwrap();
+                                            if let Some(retry_request) =
+                                                retry_request.filter(|_| {
+                                                    response_cache.lost_revalidated(&key, &response)
+                                                })
+                                            {
+                                                drop(response_cache);
+                                                // The `304 Not Modified` has nothing to swap in, ask for the whole response again
+                                                let _ = retry_sender.send_event(Message::Task(
+                                                    Box::new(move || {
+                                                        retry_handler(
+                                                            &retry_label,
+                                                            retry_request,
+                                                            Box::new(move |response| {
+                                                                let response =
+                                                                    cache.lock().unwrap().update(
+                                                                        &key,
+                                                                        response
+                                                                            .map(Cow::into_owned),
+                                                                    );
+                                                                responder.respond(response);
+                                                            }),
+                                                        );
+                                                    }),
+                                                ));
+                                                return;
+                                            }
+                                            response = response_cache.update(&key, response);
+                                        }
+                                        responder.respond(response);
+                                    }),
+                                );
+                            })));
+                            return;
                         }
-                        // Run the handler on main thread, this is needed because Tauri expects this
-                        let handler = handler.clone();
-                        let webview_label = webview_label.clone();
+                    }
+                    response_fn(None);
+                })
+                .map_err(|error| VersoRuntimeError::Spawn(error.to_string()))?;
+
+            let navigation_handler = navigation_handler.clone();
+            let current_url = webview_current_url.clone();
+            let zoom = webview_zoom.clone();
+            let windows = windows.clone();
+            let sender = event_proxy.clone();
+            if let Err(error) = webview.on_navigation_starting(move |url| {
+                let allowed = navigation_handler
+                    .as_ref()
+                    .is_none_or(|handler| handler.lock().unwrap()(&url));
+                if allowed {
+                    *current_url.lock().unwrap() = url;
+                    // Keep the zoom level across navigations like the wry runtime does,
+                    // this is done on the main thread since we can't access the controller in its own callback
+                    if *zoom.lock().unwrap() != 1.0 {
+                        let windows = windows.clone();
                         let _ = sender.send_event(Message::Task(Box::new(move || {
-                            handler(
-                                &webview_label,
-                                request,
-                                Box::new(move |response| {
-                                    response_fn(Some(response.map(Cow::into_owned)));
-                                }),
-                            );
+                            let windows = windows.lock().unwrap();
+                            let Some(window) = windows.get(&window_id) else {
+                                return;
+                            };
+                            let zoom = *window.zoom.lock().unwrap();
+                            if let Err(error) = window.webview.lock().unwrap().set_zoom(zoom) {
+                                log::error!("Failed to restore the zoom of the webview: {error}");
+                            }
                         })));
-                        return;
                     }
                 }
-                response_fn(None);
-            })
-            .map_err(|_| tauri_runtime::Error::CreateWindow)?;
+                allowed
+            }) {
+                log::error!(
+                    "Register `on_navigation_starting` failed with {error}, `navigation_handler` will not get called for this window ({webview_label})!"
+                );
+            }
+
+            let sender = event_proxy.clone();
+            webview
+                .on_close_requested(move || {
+                    let _ = sender.send_event(Message::CloseWindow(window_id));
+                })
+                .map_err(|error| VersoRuntimeError::Spawn(error.to_string()))?;
+
+            let on_page_load_handler = on_page_load_handler.clone();
+            let load_event_handler = load_event_handler.clone();
+            let current_url = webview_current_url.clone();
+            let load_progress = webview_load_progress.clone();
+            let pending_events = webview_pending_events.clone();
+            let windows = windows.clone();
+            let sender = event_proxy.clone();
+            if let Err(error) = webview.on_load_status_changed(move |status| {
+                let event = match status {
+                    verso::LoadStatus::Started => {
+                        LoadProgress::start(&load_progress);
+                        PageLoadEvent::Started
+                    }
+                    verso::LoadStatus::Complete => PageLoadEvent::Finished,
+                    _ => return,
+                };
+                // Hold or dispatch the events from `emit_to_webview` on the main thread,
+                // since we can't access the controller in its own callback, this also keeps the loads in order
+                let pending_events = pending_events.clone();
+                let windows = windows.clone();
+                let _ = sender.send_event(Message::Task(Box::new(move || match event {
+                    PageLoadEvent::Started => pending_events.lock().unwrap().start(),
+                    PageLoadEvent::Finished => {
+                        let windows = windows.lock().unwrap();
+                        if let Some(window) = windows.get(&window_id) {
+                            PendingEvents::finish(&pending_events, &window.webview);
+                        }
+                    }
+                })));
+                let url = current_url.lock().unwrap().clone();
+                if let Some(handler) = &load_event_handler {
+                    handler.0(match event {
+                        PageLoadEvent::Started => LoadEvent::Started { url: url.clone() },
+                        PageLoadEvent::Finished => LoadEvent::Finished { url: url.clone() },
+                    });
+                }
+                if let Some(handler) = &on_page_load_handler {
+                    handler.lock().unwrap()(url, event);
+                }
+            }) {
+                log::error!(
+                    "Register `on_load_status_changed` failed with {error}, `on_page_load` will not get called for this window ({webview_label})!"
+                );
+            }
+
+            let load_progress = webview_load_progress.clone();
+            if let Err(error) = webview.on_load_progress(move |progress| {
+                LoadProgress::update(&load_progress, progress * 100.0);
+            }) {
+                log::error!(
+                    "Register `on_load_progress` failed with {error}, load progress will not be reported for this window ({webview_label})!"
+                );
+            }
+
+            let new_window_handler = new_window_handler.clone();
+            let windows = windows.clone();
+            let sender = event_proxy.clone();
+            let label = webview_label.clone();
+            if let Err(error) = webview.on_new_window_requested(move |url| {
+                let Some(handler) = &new_window_handler else {
+                    log::warn!(
+                        "Window ({label}) requested to open a new window with {url}, which is ignored since there's no `on_new_window` handler"
+                    );
+                    return;
+                };
+                if handler.0(url.clone()) == NewWindowResponse::Navigate {
+                    let windows = windows.clone();
+                    let _ = sender.send_event(Message::Task(Box::new(move || {
+                        let windows = windows.lock().unwrap();
+                        let Some(window) = windows.get(&window_id) else {
+                            return;
+                        };
+                        if let Err(error) = window.webview.lock().unwrap().navigate(url) {
+                            log::error!("Failed to navigate for the new window request: {error}");
+                        }
+                    })));
+                }
+            }) {
+                log::error!(
+                    "Register `on_new_window_requested` failed with {error}, new window requests will be ignored for this window ({webview_label})!"
+                );
+            }
+
+            if navigation_finished_handler.is_some() || load_event_handler.is_some() {
+                let navigation_finished_handler = navigation_finished_handler.clone();
+                let load_event_handler = load_event_handler.clone();
+                if let Err(error) = webview.on_navigation_finished(move |url, outcome| {
+                    let outcome = match outcome {
+                        verso::NavigationOutcome::Succeeded { status } => {
+                            NavigationOutcome::Succeeded { status }
+                        }
+                        verso::NavigationOutcome::Failed { error } => {
+                            NavigationOutcome::Failed { error }
+                        }
+                        verso::NavigationOutcome::Aborted => NavigationOutcome::Aborted,
+                    };
+                    if let (Some(handler), NavigationOutcome::Failed { error }) =
+                        (&load_event_handler, &outcome)
+                    {
+                        handler.0(LoadEvent::Failed {
+                            url: url.clone(),
+                            error: error.clone(),
+                        });
+                    }
+                    if let Some(handler) = &navigation_finished_handler {
+                        handler.0(url, outcome);
+                    }
+                }) {
+                    log::error!(
+                        "Register `on_navigation_finished` failed with {error}, `on_navigation_finished` will not get called for this window ({webview_label})!"
+                    );
+                }
+            }
+
+            if let Some(handler) = mixed_content_blocked_handler.clone() {
+                if let Err(error) =
+                    webview.on_mixed_content_blocked(move |page_url, resource_url| {
+                        handler.0(page_url, resource_url);
+                    })
+                {
+                    log::error!(
+                        "Register `on_mixed_content_blocked` failed with {error}, `on_mixed_content_blocked` will not get called for this window ({webview_label})!"
+                    );
+                }
+            }
+
+            let zoom = webview_zoom.clone();
+            if let Err(error) = webview.on_zoom_changed(move |scale_factor| {
+                *zoom.lock().unwrap() = scale_factor;
+            }) {
+                log::error!(
+                    "Register `on_zoom_changed` failed with {error}, zoom changes from the hotkeys will not be kept across navigations for this window ({webview_label})!"
+                );
+            }
+
+            let sender = event_proxy.clone();
+            if let Err(error) = webview.on_theme_changed(move |theme| {
+                let _ = sender.send_event(Message::WindowEvent(
+                    window_id,
+                    WindowEvent::ThemeChanged(from_verso_theme(theme)),
+                ));
+            }) {
+                log::error!(
+                    "Register `on_theme_changed` failed with {error}, `WindowEvent::ThemeChanged` will not be emitted for this window ({webview_label})!"
+                );
+            }
+
+            // Also emitted when the window is maximized or restored,
+            // e.g. `tauri-plugin-window-state` relies on these to keep track of the window geometry
+            let sender = event_proxy.clone();
+            if let Err(error) = webview.on_resized(move |size| {
+                let _ =
+                    sender.send_event(Message::WindowEvent(window_id, WindowEvent::Resized(size)));
+            }) {
+                log::error!(
+                    "Register `on_resized` failed with {error}, `WindowEvent::Resized` will not be emitted for this window ({webview_label})!"
+                );
+            }
+
+            let sender = event_proxy.clone();
+            if let Err(error) = webview.on_moved(move |position| {
+                let _ = sender.send_event(Message::WindowEvent(
+                    window_id,
+                    WindowEvent::Moved(position),
+                ));
+            }) {
+                log::error!(
+                    "Register `on_moved` failed with {error}, `WindowEvent::Moved` will not be emitted for this window ({webview_label})!"
+                );
+            }
 
-        if let Some(navigation_handler) = pending_webview.navigation_handler {
-            if let Err(error) = webview.on_navigation_starting(move |url| navigation_handler(&url))
+            let sender = event_proxy.clone();
+            let focused = webview_focused.clone();
+            if let Err(error) = webview.on_focus_changed(move |is_focused| {
+                // Only emit on changes, since verso can report the same focus state more than once,
+                // e.g. when creating a focused window
+                if focused.swap(is_focused, Ordering::Relaxed) != is_focused {
+                    let _ = sender.send_event(Message::WindowEvent(
+                        window_id,
+                        WindowEvent::Focused(is_focused),
+                    ));
+                }
+            }) {
+                log::error!(
+                    "Register `on_focus_changed` failed with {error}, `WindowEvent::Focused` will not be emitted for this window ({webview_label})!"
+                );
+            }
+
+            let sender = event_proxy.clone();
+            if let Err(error) =
+                webview.on_scale_factor_changed(move |scale_factor, new_inner_size| {
+                    let _ = sender.send_event(Message::WindowEvent(
+                        window_id,
+                        WindowEvent::ScaleFactorChanged {
+                            scale_factor,
+                            new_inner_size,
+                        },
+                    ));
+                })
             {
                 log::error!(
-                    "Register `on_navigation_starting` failed with {error}, `navigation_handler` will not get called for this window ({label})!"
+                    "Register `on_scale_factor_changed` failed with {error}, `WindowEvent::ScaleFactorChanged` will not be emitted for this window ({webview_label})!"
                 );
             }
-        }
 
-        let sender = self.event_proxy.clone();
-        webview
-            .on_close_requested(move || {
-                let _ = sender.send_event(Message::CloseWindow(window_id));
-            })
-            .map_err(|_| tauri_runtime::Error::CreateWindow)?;
+            if drag_drop_handler_enabled {
+                let sender = event_proxy.clone();
+                if let Err(error) = webview.on_drag_drop(move |event| {
+                    let _ = sender.send_event(Message::WindowEvent(
+                        window_id,
+                        WindowEvent::DragDrop(from_verso_drag_drop_event(event)),
+                    ));
+                }) {
+                    log::error!(
+                        "Register `on_drag_drop` failed with {error}, `WindowEvent::DragDrop` will not be emitted for this window ({webview_label})!"
+                    );
+                }
+            }
+
+            let sender = event_proxy.clone();
+            webview
+                .on_exited(move || {
+                    let _ = sender.send_event(Message::VersoviewExited(window_id));
+                })
+                .map_err(|error| VersoRuntimeError::Spawn(error.to_string()))?;
+
+            Ok(webview)
+        });
+
+        let webview = spawn_versoview(url)?;
+        // Pick up the zoom restored from the profile, so it's kept across navigations and restarts
+        match webview.get_zoom() {
+            Ok(restored_zoom) => *zoom.lock().unwrap() = restored_zoom,
+            Err(error) => log::error!("Failed to get the zoom of the webview: {error}"),
+        }
 
         let on_window_event_listeners = Arc::new(Mutex::new(HashMap::new()));
+        let unload_prompt = Arc::new(AtomicBool::new(false));
 
         let webview = Arc::new(Mutex::new(webview));
         let window = Window {
             label: label.clone(),
             webview: webview.clone(),
             on_window_event_listeners: on_window_event_listeners.clone(),
+            current_url,
+            process_id: process_id.clone(),
+            zoom: zoom.clone(),
+            spawn_versoview,
+            restart_backoff: RestartBackoff::default(),
+            fullscreen: fullscreen.clone(),
+            close_requested_bridge: window_builder.close_requested_bridge,
+            close_request_pending: Arc::new(AtomicBool::new(false)),
+            unload_prompt: unload_prompt.clone(),
+            explicit_theme: explicit_theme.clone(),
         };
 
         self.windows.lock().unwrap().insert(window_id, window);
@@ -319,40 +1037,102 @@ impl<T: UserEvent> RuntimeContext<T> {
                 context: self.clone(),
                 webview: webview.clone(),
                 on_window_event_listeners,
+                process_id: process_id.clone(),
+                fullscreen: fullscreen.clone(),
+                focused,
+                explicit_theme,
+                unload_prompt,
             },
             webview: Some(DetachedWindowWebview {
                 webview: DetachedWebview {
-                    label,
+                    label: detached_webview_label,
                     dispatcher: VersoWebviewDispatcher {
                         id: webview_id,
+                        window_id,
                         context: self.clone(),
                         webview,
+                        load_progress,
+                        process_id,
+                        zoom,
+                        user_scripts,
+                        fullscreen,
+                        pending_events,
                     },
                 },
-                use_https_scheme: false,
+                use_https_scheme,
             }),
         })
     }
 
+    /// Shuts down the versoview processes of the windows left after the event loop exits,
+    /// since [`std::process::exit`] doesn't run the destructors
+    fn exit_all_versoviews(&self) {
+        let windows = std::mem::take(&mut *self.windows.lock().unwrap());
+        for window in windows.into_values() {
+            if let Err(error) = window.webview.lock().unwrap().exit() {
+                log::error!("Failed to exit the webview: {error}");
+            }
+        }
+    }
+
+    /// Checks if the page wants a `beforeunload` prompt on a background thread, since it waits on versoview,
+    /// and then continues the close request on the main thread with [`Message::CloseWindowChecked`]
+    pub fn check_unload_prompt(&self, id: WindowId) {
+        let (webview, unload_prompt) = {
+            let windows = self.windows.lock().unwrap();
+            let Some(window) = windows.get(&id) else {
+                return;
+            };
+            (window.webview.clone(), window.unload_prompt.clone())
+        };
+        let event_proxy = self.event_proxy.clone();
+        spawn(move || {
+            let wants_unload_prompt = webview.lock().unwrap().has_unload_prompt().unwrap_or(false);
+            unload_prompt.store(wants_unload_prompt, Ordering::Relaxed);
+            let _ = event_proxy.send_event(Message::CloseWindowChecked(id));
+        });
+    }
+
     /// Handles the close window request by sending the [`WindowEvent::CloseRequested`] event
     /// if the request doesn't request a forced close
-    /// and if not prevented, send [`WindowEvent::Destroyed`]
+    /// and if not prevented (by the handlers or by the unload prompt from [`Self::check_unload_prompt`]), send [`WindowEvent::Destroyed`]
     /// then checks if there're windows left, if not, send [`RunEvent::ExitRequested`]
     /// returns if we should exit the event loop
-    pub fn handle_close_window_request<F: FnMut(RunEvent<T>) + 'static>(
+    pub fn handle_close_window_request<F: FnMut(RunEvent<T>)>(
         &self,
         callback: &mut F,
         id: WindowId,
         force: bool,
     ) -> bool {
-        let mut windows = self.windows.lock().unwrap();
-        let Some(window) = windows.get(&id) else {
-            return false;
+        // Don't hold the lock while calling the callbacks,
+        // they may read the windows, e.g. through `VersoRuntimeHandle::window_labels`
+        let (
+            label,
+            webview,
+            on_window_event_listeners,
+            close_requested_bridge,
+            close_request_pending,
+            unload_prompt,
+        ) = {
+            let windows = self.windows.lock().unwrap();
+            let Some(window) = windows.get(&id) else {
+                return false;
+            };
+            (
+                window.label.clone(),
+                window.webview.clone(),
+                window.on_window_event_listeners.clone(),
+                window.close_requested_bridge,
+                window.close_request_pending.clone(),
+                window.unload_prompt.clone(),
+            )
         };
-        let label = window.label.clone();
-        let on_window_event_listeners = window.on_window_event_listeners.clone();
 
         if !force {
+            // The page's `beforeunload` handlers ran in `check_unload_prompt`, if one of them wants to prompt the user,
+            // we prevent the close and leave it to the app to confirm and call `destroy` on the window
+            let wants_unload_prompt = unload_prompt.load(Ordering::Relaxed);
+
             let (tx, rx) = channel();
             let window_event = WindowEvent::CloseRequested {
                 signal_tx: tx.clone(),
@@ -365,46 +1145,440 @@ impl<T: UserEvent> RuntimeContext<T> {
                 event: WindowEvent::CloseRequested { signal_tx: tx },
             });
 
-            let should_prevent = matches!(rx.try_recv(), Ok(true));
+            let should_prevent = wants_unload_prompt || matches!(rx.try_recv(), Ok(true));
             if should_prevent {
                 return false;
             }
+
+            if close_requested_bridge {
+                if !close_request_pending.swap(true, Ordering::Relaxed) {
+                    self.request_close_from_page(id, webview, close_request_pending);
+                }
+                return false;
+            }
         }
 
+        drop(webview);
+
+        // The window could have been removed while the callbacks were running
+        let Some(window) = self.windows.lock().unwrap().remove(&id) else {
+            return false;
+        };
         let webview_weak = std::sync::Arc::downgrade(&window.webview);
+        drop(window);
+        callback(RunEvent::WindowEvent {
+            label,
+            event: WindowEvent::Destroyed,
+        });
+
+        // This is required becuase tauri puts in a clone of the window in to WindowEventHandler closure,
+        // and we need to clear it for the window to drop or else it will stay there forever
+        on_window_event_listeners.lock().unwrap().clear();
+
+        if let Some(webview) = webview_weak.upgrade() {
+            log::warn!(
+                "The versoview controller reference count is not 0 on window close, \
+                there're leaks happening, shutting down this versoview instance regardless"
+            );
+            if let Err(error) = webview.lock().unwrap().exit() {
+                log::error!("Failed to exit the webview: {error}");
+            }
+        }
+
+        let is_empty = self.windows.lock().unwrap().is_empty();
+        if !is_empty {
+            return false;
+        }
+
+        let (tx, rx) = channel();
+        callback(RunEvent::ExitRequested { code: None, tx });
+
+        let recv = rx.try_recv();
+        let should_prevent = matches!(recv, Ok(ExitRequestedEventAction::Prevent));
+
+        !should_prevent
+    }
+
+    /// Sends the [`WindowEvent`] to the listeners of the window and then to the app
+    pub fn handle_window_event<F: FnMut(RunEvent<T>)>(
+        &self,
+        callback: &mut F,
+        id: WindowId,
+        event: WindowEvent,
+    ) {
+        let (label, on_window_event_listeners, fullscreen, webview) = {
+            let windows = self.windows.lock().unwrap();
+            let Some(window) = windows.get(&id) else {
+                return;
+            };
+            (
+                window.label.clone(),
+                window.on_window_event_listeners.clone(),
+                window.fullscreen.clone(),
+                window.webview.clone(),
+            )
+        };
+        // Entering or leaving fullscreen resizes the window,
+        // pick up the ones done through the OS so the geometry set while fullscreen is applied
+        if matches!(event, WindowEvent::Resized(_)) {
+            if let Err(error) = FullscreenState::resized(&fullscreen, &webview) {
+                log::error!(
+                    "Failed to update the fullscreen state of the window ({label}): {error}"
+                );
+            }
+        }
+        for handler in on_window_event_listeners.lock().unwrap().values() {
+            handler(&event);
+        }
+        callback(RunEvent::WindowEvent { label, event });
+    }
+
+    /// Checks if the system theme changed since the last check, and if the windows are following it
+    /// (there's no [`Self::prefered_theme`] and the window doesn't have an explicit theme),
+    /// applies it to their webviews and sends [`WindowEvent::ThemeChanged`] to them
+    ///
+    /// There're no theme change events without a window in this process, so this is polled
+    pub fn handle_system_theme_changed<F: FnMut(RunEvent<T>)>(&self, callback: &mut F) {
+        let Some(theme) = self.query_system_theme() else {
+            return;
+        };
+        let previous_theme = self.system_theme.lock().unwrap().replace(Some(theme));
+        if previous_theme == Some(Some(theme)) || self.prefered_theme.lock().unwrap().is_some() {
+            return;
+        }
+        let following_windows: Vec<_> = self
+            .windows
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, window)| window.explicit_theme.lock().unwrap().is_none())
+            .map(|(id, window)| (*id, window.webview.clone()))
+            .collect();
+        for (id, webview) in following_windows {
+            if let Err(error) = webview
+                .lock()
+                .unwrap()
+                .set_theme(Some(to_verso_theme(theme)))
+            {
+                log::error!("Failed to set the theme for webview: {error}");
+            }
+            self.handle_window_event(callback, id, WindowEvent::ThemeChanged(theme));
+        }
+    }
+
+    /// Asks the page whether to close the window through the script added by
+    /// [`VersoWindowBuilder::close_requested_bridge`](crate::VersoWindowBuilder::close_requested_bridge)
+    /// on a background thread, and destroys the window if the page allows it or doesn't settle it in time
+    fn request_close_from_page(
+        &self,
+        id: WindowId,
+        webview: Arc<Mutex<VersoviewController>>,
+        close_request_pending: Arc<AtomicBool>,
+    ) {
+        let event_proxy = self.event_proxy.clone();
+        spawn(move || {
+            let started = Instant::now();
+            let mut script = "window.__TAURI_VERSO_CLOSE_REQUESTED__.request()";
+            let allowed = loop {
+                let (tx, rx) = channel();
+                let sent = webview.lock().unwrap().execute_script_with_callback(
+                    script.to_owned(),
+                    move |result| {
+                        let _ = tx.send(result);
+                    },
+                );
+                let state = match sent
+                    .ok()
+                    .and_then(|_| rx.recv_timeout(CLOSE_REQUESTED_TIMEOUT).ok())
+                {
+                    Some(Ok(Some(serde_json::Value::String(state)))) => state,
+                    // The page doesn't have the script (e.g. it failed to load) or is not responding
+                    _ => break true,
+                };
+                match state.as_str() {
+                    "allow" => break true,
+                    "cancel" => break false,
+                    _ if started.elapsed() >= CLOSE_REQUESTED_TIMEOUT => {
+                        log::warn!(
+                            "The page didn't allow or cancel closing the window in {CLOSE_REQUESTED_TIMEOUT:?}, closing it anyway"
+                        );
+                        break true;
+                    }
+                    _ => {}
+                }
+                script = "window.__TAURI_VERSO_CLOSE_REQUESTED__.state()";
+                sleep(CLOSE_REQUESTED_POLL_INTERVAL);
+            };
+            close_request_pending.store(false, Ordering::Relaxed);
+            if allowed {
+                let _ = event_proxy.send_event(Message::DestroyWindow(id));
+            }
+        });
+    }
+
+    /// Checks if a monitor has been removed since the last check,
+    /// and moves the windows that are not visible on any monitor anymore onto the first monitor
+    pub fn handle_monitors_changed(&self, event_loop: &TaoEventLoopWindowTarget<Message<T>>) {
+        self.update_monitors(event_loop.tauri_available_monitors());
+    }
+
+    /// Replaces the last known monitors with `monitors`,
+    /// if a monitor has been removed, moves the windows that are not visible on any monitor anymore onto the first monitor,
+    /// see [`Self::handle_monitors_changed`]
+    pub(crate) fn update_monitors(&self, monitors: Vec<Monitor>) {
+        let previous_monitors =
+            std::mem::replace(&mut *self.monitors.lock().unwrap(), monitors.clone());
+        if !is_monitor_removed(&previous_monitors, &monitors) {
+            return;
+        }
+
+        // Leave fullscreen for the windows fullscreen on a removed monitor,
+        // so they're moved onto a remaining monitor below
+        for window in self.windows.lock().unwrap().values() {
+            let mut state = window.fullscreen.lock().unwrap();
+            let monitor_removed = state
+                .monitor
+                .as_ref()
+                .is_some_and(|fullscreen_monitor| !has_monitor(&monitors, fullscreen_monitor));
+            if monitor_removed {
+                if let Err(error) = state.leave(&*window.webview.lock().unwrap()) {
+                    log::error!("Failed to leave fullscreen on the removed monitor: {error}");
+                }
+            }
+        }
+
+        let windows = self
+            .windows
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|window| !window.fullscreen.lock().unwrap().fullscreen)
+            .map(|window| window.webview.clone())
+            .collect::<Vec<_>>();
+        // `WindowEvent::Moved` is emitted from versoview's `on_moved` notifications
+        for webview in windows {
+            let webview = webview.lock().unwrap();
+            // The position is not available on Wayland, the compositor takes care of it there
+            let (Ok(Some(position)), Ok(size)) =
+                (webview.get_outer_position(), webview.get_outer_size())
+            else {
+                continue;
+            };
+            let Some((position, _)) = clamp_to_monitors(position, size, &monitors) else {
+                continue;
+            };
+            if let Err(error) = webview.set_position(position.into()) {
+                log::error!("Failed to move the window off the removed monitor: {error}");
+            }
+        }
+    }
+
+    /// Handles the versoview process of a window exiting,
+    /// this is ignored if the window is already closed,
+    /// otherwise notifies the handler set by [`VersoRuntimeConfig::on_verso_exited`]
+    /// and schedules a restart if [`VersoRuntimeConfig::auto_restart`] is enabled
+    pub fn handle_versoview_exited(&self, id: WindowId) {
+        let mut windows = self.windows.lock().unwrap();
+        let Some(window) = windows.get_mut(&id) else {
+            return;
+        };
+        let label = window.label.clone();
+        window.process_id.store(0, Ordering::Relaxed);
+        log::error!("The versoview process of window ({label}) exited unexpectedly");
+
+        let restart_delay = if self.config().auto_restart {
+            let delay = window.restart_backoff.next_delay();
+            if delay.is_none() {
+                log::error!(
+                    "The versoview process of window ({label}) keeps exiting, giving up restarting it"
+                );
+            }
+            delay
+        } else {
+            None
+        };
+        drop(windows);
+
+        if let Some(handler) = &self.config().on_verso_exited {
+            (handler.0)(&label);
+        }
+
+        if let Some(delay) = restart_delay {
+            let sender = self.event_proxy.clone();
+            spawn(move || {
+                sleep(delay);
+                let _ = sender.send_event(Message::RestartVersoview(id));
+            });
+        }
+    }
+
+    /// Spawns a new versoview process for the window at its last URL,
+    /// and swaps it in place of the exited one
+    pub fn restart_versoview(&self, id: WindowId) {
+        // Don't hold the windows lock while spawning and waiting for the new process to report its version
+        let (label, url, zoom, webview, spawn_versoview) = {
+            let windows = self.windows.lock().unwrap();
+            let Some(window) = windows.get(&id) else {
+                return;
+            };
+            (
+                window.label.clone(),
+                window.current_url.lock().unwrap().clone(),
+                window.zoom.clone(),
+                window.webview.clone(),
+                window.spawn_versoview.clone(),
+            )
+        };
+        log::info!("Restarting the versoview process of window ({label}) at {url}");
+        let new_webview = match spawn_versoview(url) {
+            Ok(new_webview) => new_webview,
+            Err(error) => {
+                log::error!("Failed to restart the versoview process of window ({label}): {error}");
+                return;
+            }
+        };
+        // The window could be closed while we were spawning
+        if !self.windows.lock().unwrap().contains_key(&id) {
+            return;
+        }
+        let zoom = *zoom.lock().unwrap();
+        if zoom != 1.0 {
+            if let Err(error) = new_webview.set_zoom(zoom) {
+                log::error!("Failed to restore the zoom of the webview: {error}");
+            }
+        }
+        *webview.lock().unwrap() = new_webview;
+    }
+}
+
+/// Makes sure a web resource request handled by a custom protocol always gets a response,
+/// if the handler drops the responder without responding, we respond with an error,
+/// or else the request will hang forever in versoview
+struct WebResourceResponder<F: FnOnce(Option<http::Response<Vec<u8>>>)> {
+    response_fn: Option<F>,
+}
+
+impl<F: FnOnce(Option<http::Response<Vec<u8>>>)> WebResourceResponder<F> {
+    fn new(response_fn: F) -> Self {
+        Self {
+            response_fn: Some(response_fn),
+        }
+    }
+
+    fn respond(mut self, response: http::Response<Vec<u8>>) {
+        if let Some(response_fn) = self.response_fn.take() {
+            response_fn(Some(response));
+        }
+    }
+}
+
+impl<F: FnOnce(Option<http::Response<Vec<u8>>>)> Drop for WebResourceResponder<F> {
+    fn drop(&mut self) {
+        if let Some(response_fn) = self.response_fn.take() {
+            log::warn!("The custom protocol handler dropped the responder without responding");
+            let mut response = http::Response::new(Vec::new());
+            *response.status_mut() = http::StatusCode::INTERNAL_SERVER_ERROR;
+            response_fn(Some(response));
+        }
+    }
+}
+
+/// Returns if `monitors` has `monitor`, the monitors don't have a stable id,
+/// so they're compared by their name, position and size
+fn has_monitor(monitors: &[Monitor], monitor: &Monitor) -> bool {
+    monitors.iter().any(|other| {
+        other.name == monitor.name
+            && other.position == monitor.position
+            && other.size == monitor.size
+    })
+}
 
-        windows.remove(&id);
-        callback(RunEvent::WindowEvent {
-            label,
-            event: WindowEvent::Destroyed,
-        });
+/// Returns if a monitor in `previous_monitors` is not in `monitors` anymore
+fn is_monitor_removed(previous_monitors: &[Monitor], monitors: &[Monitor]) -> bool {
+    previous_monitors
+        .iter()
+        .any(|previous_monitor| !has_monitor(monitors, previous_monitor))
+}
 
-        // This is required becuase tauri puts in a clone of the window in to WindowEventHandler closure,
-        // and we need to clear it for the window to drop or else it will stay there forever
-        on_window_event_listeners.lock().unwrap().clear();
+/// Checks if the version of the spawned versoview is in [`SUPPORTED_VERSOVIEW_VERSIONS`],
+/// versoview builds that can't report a version we understand (e.g. custom builds) are let through with a warning
+fn check_versoview_version(
+    webview: &VersoviewController,
+) -> std::result::Result<(), VersoRuntimeError> {
+    let Ok(version) = webview.get_version() else {
+        log::warn!(
+            "The versoview process didn't report its version, it may not work with this version of tauri-runtime-verso"
+        );
+        return Ok(());
+    };
+    let Some(parsed_version) = parse_version(&version) else {
+        log::warn!(
+            "The versoview process reported an unrecognized version {version}, it may not work with this version of tauri-runtime-verso"
+        );
+        return Ok(());
+    };
+    if SUPPORTED_VERSOVIEW_VERSIONS.contains(&parsed_version) {
+        return Ok(());
+    }
+    let (start, end) = SUPPORTED_VERSOVIEW_VERSIONS.into_inner();
+    Err(VersoRuntimeError::UnsupportedVersion {
+        found: version,
+        supported: if start == end {
+            format!("{}.{}.{}", start.0, start.1, start.2)
+        } else {
+            format!(
+                "{}.{}.{} to {}.{}.{}",
+                start.0, start.1, start.2, end.0, end.1, end.2
+            )
+        },
+    })
+}
 
-        if let Some(webview) = webview_weak.upgrade() {
-            log::warn!(
-                "The versoview controller reference count is not 0 on window close, \
-                there're leaks happening, shutting down this versoview instance regardless"
-            );
-            if let Err(error) = webview.lock().unwrap().exit() {
-                log::error!("Failed to exit the webview: {error}");
+/// Forwards the stdout and stderr of the versoview process to the [`log`] crate,
+/// using the window label as the log target
+fn forward_versoview_output(webview: &mut VersoviewController, label: &str) {
+    if let Some(stdout) = webview.take_stdout() {
+        let target = label.to_owned();
+        spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(io::Result::ok) {
+                log::info!(target: &target, "{line}");
             }
-        }
+        });
+    }
+    if let Some(stderr) = webview.take_stderr() {
+        let target = label.to_owned();
+        spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(io::Result::ok) {
+                log::error!(target: &target, "{line}");
+            }
+        });
+    }
+}
 
-        let is_empty = windows.is_empty();
-        if !is_empty {
+/// Checks if the request is for the custom protocol `scheme`,
+/// and reverts the URI work around on Windows if it is
+fn match_custom_protocol_uri(
+    request: &mut http::Request<Vec<u8>>,
+    scheme: &str,
+    use_https_scheme: bool,
+) -> bool {
+    #[cfg(windows)]
+    {
+        let uri = request.uri().to_string();
+        let http_or_https = if use_https_scheme { "https" } else { "http" };
+        if !is_work_around_uri(&uri, http_or_https, scheme) {
             return false;
         }
-
-        let (tx, rx) = channel();
-        callback(RunEvent::ExitRequested { code: None, tx });
-
-        let recv = rx.try_recv();
-        let should_prevent = matches!(recv, Ok(ExitRequestedEventAction::Prevent));
-
-        !should_prevent
+        if let Ok(reverted) = revert_custom_protocol_work_around(&uri, http_or_https, scheme) {
+            *request.uri_mut() = reverted
+        } else {
+            log::error!("Can't revert the URI work around on: {uri}")
+        };
+        true
+    }
+    #[cfg(not(windows))]
+    {
+        request.uri().scheme_str() == Some(scheme)
     }
 }
 
@@ -456,6 +1630,191 @@ pub struct VersoRuntimeHandle<T: UserEvent> {
     context: RuntimeContext<T>,
 }
 
+impl<T: UserEvent> VersoRuntimeHandle<T> {
+    /// Captures the URL, geometry and zoom of all the windows,
+    /// the result can be serialized and restored with [`VersoRuntimeHandle::import_session`] on next launch
+    pub fn export_session(&self) -> Result<SessionState> {
+        let windows = self
+            .context
+            .windows
+            .lock()
+            .unwrap()
+            .values()
+            .map(|window| {
+                (
+                    window.label.clone(),
+                    window.webview.clone(),
+                    window.current_url.lock().unwrap().to_string(),
+                    *window.zoom.lock().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut session = SessionState::default();
+        for (label, webview, url, zoom) in windows {
+            let webview = webview.lock().unwrap();
+            let position = webview
+                .get_outer_position()
+                .map_err(|_| Error::FailedToSendMessage)?
+                .unwrap_or_default();
+            let size = webview
+                .get_inner_size()
+                .map_err(|_| Error::FailedToSendMessage)?;
+            session.windows.push(WindowState {
+                label,
+                url,
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+                zoom,
+                maximized: webview
+                    .is_maximized()
+                    .map_err(|_| Error::FailedToSendMessage)?,
+                fullscreen: webview
+                    .is_fullscreen()
+                    .map_err(|_| Error::FailedToSendMessage)?,
+            });
+        }
+        Ok(session)
+    }
+
+    /// Restores the windows from a [`SessionState`] exported by [`VersoRuntimeHandle::export_session`],
+    /// positions not visible on any monitor anymore are moved to the first monitor
+    ///
+    /// The runtime can't create windows on its own since they need to be set up by Tauri,
+    /// so this restores the windows that are already open with the same label,
+    /// and returns the states of the windows that are not open,
+    /// create them with [`tauri::WebviewWindowBuilder`] using the returned URL and geometry
+    pub fn import_session(&self, session: SessionState) -> Result<Vec<WindowState>> {
+        let monitors = self.context.available_monitors()?;
+        let mut missing_windows = Vec::new();
+        for mut state in session.windows {
+            state.clamp_to_monitors(&monitors);
+            let window = self
+                .context
+                .windows
+                .lock()
+                .unwrap()
+                .values()
+                .find(|window| window.label == state.label)
+                .map(|window| {
+                    (
+                        window.webview.clone(),
+                        window.zoom.clone(),
+                        window.fullscreen.clone(),
+                    )
+                });
+            let Some((webview, zoom, fullscreen)) = window else {
+                missing_windows.push(state);
+                continue;
+            };
+            let mut fullscreen = fullscreen.lock().unwrap();
+            let webview = webview.lock().unwrap();
+            let result = (|| {
+                webview.set_fullscreen(false)?;
+                webview.set_maximized(false)?;
+                webview.set_position(PhysicalPosition::new(state.x, state.y).into())?;
+                webview.set_size(PhysicalSize::new(state.width, state.height).into())?;
+                webview.set_zoom(state.zoom)?;
+                if let Ok(url) = Url::parse(&state.url) {
+                    webview.navigate(url)?;
+                }
+                webview.set_maximized(state.maximized)?;
+                webview.set_fullscreen(state.fullscreen)
+            })();
+            result.map_err(|_| Error::FailedToSendMessage)?;
+            *zoom.lock().unwrap() = state.zoom;
+            *fullscreen = FullscreenState {
+                fullscreen: state.fullscreen,
+                ..Default::default()
+            };
+        }
+        Ok(missing_windows)
+    }
+
+    /// Queries the available monitors on the main thread, waiting for the main thread to handle it,
+    /// unlike [`RuntimeHandle::available_monitors`] which may return the last known monitors
+    pub fn refresh_available_monitors(&self) -> Result<Vec<Monitor>> {
+        self.context.refresh_available_monitors()
+    }
+
+    /// Returns the labels of the open windows
+    ///
+    /// This doesn't go through the main thread, so it's safe to call from anywhere,
+    /// including the window event handlers
+    pub fn window_labels(&self) -> Vec<String> {
+        self.context
+            .windows
+            .lock()
+            .unwrap()
+            .values()
+            .map(|window| window.label.clone())
+            .collect()
+    }
+
+    /// Returns the number of open windows, see [`Self::window_labels`]
+    pub fn window_count(&self) -> usize {
+        self.context.windows.lock().unwrap().len()
+    }
+
+    /// Evaluates the script in every open window,
+    /// returns the labels of the windows that failed along with their errors if any of them failed
+    pub fn eval_script_all(
+        &self,
+        script: impl Into<String>,
+    ) -> std::result::Result<(), Vec<(String, Error)>> {
+        let script = script.into();
+        // Collect the webviews first so we don't hold the windows lock while locking the webviews
+        let webviews: Vec<_> = self
+            .context
+            .windows
+            .lock()
+            .unwrap()
+            .values()
+            .map(|window| (window.label.clone(), window.webview.clone()))
+            .collect();
+        let errors: Vec<_> = webviews
+            .into_iter()
+            .filter_map(|(label, webview)| {
+                webview
+                    .lock()
+                    .unwrap()
+                    .execute_script(script.clone())
+                    .err()
+                    .map(|_| (label, Error::FailedToSendMessage))
+            })
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Queries the video modes supported by the monitor on the main thread,
+    /// returns `None` if the monitor is not connected anymore
+    pub fn monitor_video_modes(&self, monitor: &Monitor) -> Result<Option<Vec<VideoMode>>> {
+        let monitor = monitor.clone();
+        self.context
+            .run_on_main_thread_with_event_loop(move |e| e.tauri_monitor_video_modes(&monitor))
+    }
+
+    /// Returns the refresh rate of the monitor in hertz,
+    /// this is the highest refresh rate among the video modes at the monitor's current resolution,
+    /// see [`VideoMode::refresh_rate`],
+    /// returns `None` if the monitor is not connected anymore or the platform doesn't report any video modes
+    pub fn monitor_refresh_rate(&self, monitor: &Monitor) -> Result<Option<u16>> {
+        let size = monitor.size;
+        let video_modes = self.monitor_video_modes(monitor)?.unwrap_or_default();
+        Ok(video_modes
+            .iter()
+            .filter(|video_mode| video_mode.size == size)
+            .map(|video_mode| video_mode.refresh_rate)
+            .max())
+    }
+}
+
 impl<T: UserEvent> RuntimeHandle<T> for VersoRuntimeHandle<T> {
     type Runtime = VersoRuntime<T>;
 
@@ -463,21 +1822,31 @@ impl<T: UserEvent> RuntimeHandle<T> for VersoRuntimeHandle<T> {
         EventProxy(self.context.event_proxy.clone())
     }
 
-    /// Unsupported, has no effect
+    /// Sets the activation policy of this process,
+    /// note the versoview processes of the windows have their own
     #[cfg(target_os = "macos")]
     #[cfg_attr(docsrs, doc(cfg(target_os = "macos")))]
     fn set_activation_policy(
         &self,
         activation_policy: tauri_runtime::ActivationPolicy,
     ) -> Result<()> {
-        Ok(())
+        use tao::platform::macos::EventLoopWindowTargetExtMacOS;
+        self.context
+            .run_on_main_thread_with_event_loop(move |event_loop| {
+                event_loop
+                    .set_activation_policy_at_runtime(to_tao_activation_policy(activation_policy))
+            })
     }
 
-    /// Unsupported, has no effect
+    /// Shows or hides the dock icon of this process
     #[cfg(target_os = "macos")]
     #[cfg_attr(docsrs, doc(cfg(target_os = "macos")))]
     fn set_dock_visibility(&self, visible: bool) -> Result<()> {
-        Ok(())
+        use tao::platform::macos::EventLoopWindowTargetExtMacOS;
+        self.context
+            .run_on_main_thread_with_event_loop(move |event_loop| {
+                event_loop.set_dock_visibility(visible)
+            })
     }
 
     fn request_exit(&self, code: i32) -> Result<()> {
@@ -524,10 +1893,13 @@ impl<T: UserEvent> RuntimeHandle<T> for VersoRuntimeHandle<T> {
             .flatten()
     }
 
+    /// Returns the last known monitors immediately when called from a background thread,
+    /// see [`VersoRuntimeHandle::refresh_available_monitors`] for getting the latest monitors
     fn available_monitors(&self) -> Vec<Monitor> {
-        self.context
-            .run_on_main_thread_with_event_loop(|e| e.tauri_available_monitors())
-            .unwrap()
+        self.context.available_monitors().unwrap_or_else(|error| {
+            log::error!("Failed to get the available monitors: {error}");
+            Vec::new()
+        })
     }
 
     fn cursor_position(&self) -> Result<PhysicalPosition<f64>> {
@@ -537,39 +1909,43 @@ impl<T: UserEvent> RuntimeHandle<T> for VersoRuntimeHandle<T> {
 
     fn set_theme(&self, theme: Option<Theme>) {
         *self.context.prefered_theme.lock().unwrap() = theme;
-        for window in self.context.windows.lock().unwrap().values() {
-            if let Err(error) = window
-                .webview
-                .lock()
-                .unwrap()
-                .set_theme(theme.map(to_verso_theme))
-            {
-                log::error!("Failed to set the theme for webview: {error}");
-            }
-        }
+        self.context.apply_default_theme();
         let _ = self
             .context
             .run_on_main_thread_with_event_loop(move |e| e.set_theme(theme.map(to_tao_theme)));
     }
 
-    /// Unsupported, has no effect
+    /// Shows the application (`NSApplication`) of this process,
+    /// note the windows of the versoview processes are not affected
     #[cfg(target_os = "macos")]
     fn show(&self) -> Result<()> {
-        Ok(())
+        use tao::platform::macos::EventLoopWindowTargetExtMacOS;
+        self.context
+            .run_on_main_thread_with_event_loop(|event_loop| event_loop.show_application())
     }
 
-    /// Unsupported, has no effect
+    /// Hides the application (`NSApplication`) of this process,
+    /// note the windows of the versoview processes are not affected
     #[cfg(target_os = "macos")]
     fn hide(&self) -> Result<()> {
-        Ok(())
+        use tao::platform::macos::EventLoopWindowTargetExtMacOS;
+        self.context
+            .run_on_main_thread_with_event_loop(|event_loop| event_loop.hide_application())
     }
 
-    /// Unsupported, will always return an error
+    /// Returns the display handle of the event loop of this process, not the ones of the versoview processes
+    ///
+    /// Raw handles can't be used across threads,
+    /// so this returns [`raw_window_handle::HandleError::Unavailable`] if not called on the main thread
     fn display_handle(
         &self,
     ) -> std::result::Result<raw_window_handle::DisplayHandle<'_>, raw_window_handle::HandleError>
     {
-        Err(raw_window_handle::HandleError::NotSupported)
+        use raw_window_handle::HasDisplayHandle;
+        if current_thread().id() != self.context.main_thread_id {
+            return Err(raw_window_handle::HandleError::Unavailable);
+        }
+        self.context.main_thread.window_target.display_handle()
     }
 
     /// Unsupported, has no effect, the callback will not be called
@@ -610,13 +1986,152 @@ impl<T: UserEvent> EventLoopProxy<T> for EventProxy<T> {
 pub struct VersoRuntime<T: UserEvent = tauri::EventLoopMessage> {
     pub context: RuntimeContext<T>,
     event_loop: EventLoop<Message<T>>,
+    lifecycle: Lifecycle,
+}
+
+/// Tracks if [`RunEvent::Ready`] and [`RunEvent::Exit`] have been emitted, so they're emitted exactly once
+/// even though [`Runtime::run_iteration`] starts the event loop multiple times
+#[derive(Debug, Default)]
+struct Lifecycle {
+    ready: bool,
+    exited: bool,
+}
+
+impl Lifecycle {
+    /// Emits the [`RunEvent`] of a lifecycle event from the event loop, see [`handle_event_loop`]
+    /// for how they're mapped, other events are ignored
+    fn handle_event<T: UserEvent, U>(
+        &mut self,
+        event: &TaoEvent<'_, U>,
+        callback: &mut impl FnMut(RunEvent<T>),
+    ) {
+        match event {
+            TaoEvent::NewEvents(StartCause::Init) if !self.ready => {
+                self.ready = true;
+                callback(RunEvent::Ready);
+            }
+            // `StartCause::Poll` only means the event loop woke up to poll,
+            // so only emit `Resumed` when the app is actually resumed
+            TaoEvent::Resumed => callback(RunEvent::Resumed),
+            TaoEvent::MainEventsCleared => callback(RunEvent::MainEventsCleared),
+            TaoEvent::LoopDestroyed => self.exit(callback),
+            _ => {}
+        }
+    }
+
+    /// Emits [`RunEvent::Exit`] if it's not emitted yet
+    fn exit<T: UserEvent>(&mut self, callback: &mut impl FnMut(RunEvent<T>)) {
+        if !self.exited {
+            self.exited = true;
+            callback(RunEvent::Exit);
+        }
+    }
 }
 
 impl<T: UserEvent> VersoRuntime<T> {
-    fn init(event_loop: EventLoop<Message<T>>) -> Self {
+    /// Creates the runtime with `config`, like [`Runtime::new`] which uses the default config
+    ///
+    /// [`tauri::Builder`] always creates its runtime with [`Runtime::new`],
+    /// use [`crate::builder_with_config`] to configure the runtime of a tauri app
+    pub fn new_with_config(args: RuntimeInitArgs, config: VersoRuntimeConfig) -> Result<Self> {
+        Self::create(args, Some(config))
+    }
+
+    /// Creates the runtime with `config` on any thread, like [`Runtime::new_any_thread`] which uses the default config
+    #[cfg(any(windows, target_os = "linux"))]
+    pub fn new_any_thread_with_config(
+        args: RuntimeInitArgs,
+        config: VersoRuntimeConfig,
+    ) -> Result<Self> {
+        Self::create_any_thread(args, Some(config))
+    }
+
+    /// Creates the runtime, with the config from [`crate::builder_with_config`] if `config` is `None`,
+    /// see [`set_runtime_config`]
+    fn create(args: RuntimeInitArgs, config: Option<VersoRuntimeConfig>) -> Result<Self> {
+        let event_loop_builder = EventLoopBuilder::<Message<T>>::with_user_event();
+        Ok(Self::init_with_builder(event_loop_builder, args, config))
+    }
+
+    #[cfg(any(windows, target_os = "linux"))]
+    fn create_any_thread(
+        args: RuntimeInitArgs,
+        config: Option<VersoRuntimeConfig>,
+    ) -> Result<Self> {
+        let mut event_loop_builder = EventLoopBuilder::<Message<T>>::with_user_event();
+        #[cfg(target_os = "linux")]
+        use tao::platform::unix::EventLoopBuilderExtUnix;
+        #[cfg(windows)]
+        use tao::platform::windows::EventLoopBuilderExtWindows;
+        event_loop_builder.with_any_thread(true);
+        Ok(Self::init_with_builder(event_loop_builder, args, config))
+    }
+
+    /// Runs the event loop without consuming the runtime, dispatching the same [`RunEvent`]s as [`Runtime::run_return`],
+    /// returns after processing the pending events if there're some, otherwise after new events come in
+    /// or `timeout` passes, whichever comes first, useful for driving the app step by step in tests
+    ///
+    /// Returns the exit code if the app exited (e.g. the last window is closed), the runtime shouldn't be pumped after that
+    pub fn pump_events<F: FnMut(RunEvent<T>)>(
+        &mut self,
+        timeout: Duration,
+        mut callback: F,
+    ) -> Option<i32> {
+        let Self {
+            context,
+            event_loop,
+            lifecycle,
+            ..
+        } = self;
+        let deadline = Instant::now() + timeout;
+        let mut processed_events = false;
+        let mut exit_code = None;
+        // We stopped the event loop to return to the caller, as opposed to the app exiting
+        let mut returning = false;
+        event_loop.run_return(|event, event_loop, control_flow| {
+            let events_cleared = matches!(event, TaoEvent::MainEventsCleared);
+            match event {
+                TaoEvent::LoopDestroyed if exit_code.is_none() => return,
+                TaoEvent::UserEvent(_) => processed_events = true,
+                _ => {}
+            }
+            handle_event_loop(
+                context,
+                lifecycle,
+                &mut callback,
+                event,
+                event_loop,
+                control_flow,
+            );
+            if returning || exit_code.is_some() {
+                return;
+            }
+            match control_flow {
+                ControlFlow::Exit => exit_code = Some(0),
+                ControlFlow::ExitWithCode(code) => exit_code = Some(*code),
+                _ if events_cleared && (processed_events || Instant::now() >= deadline) => {
+                    returning = true;
+                    *control_flow = ControlFlow::Exit;
+                }
+                _ => *control_flow = ControlFlow::WaitUntil(deadline),
+            }
+        });
+        if exit_code.is_some() {
+            lifecycle.exit(&mut callback);
+            context.exit_all_versoviews();
+        }
+        exit_code
+    }
+
+    fn init(
+        event_loop: EventLoop<Message<T>>,
+        app_id: Option<String>,
+        config: Option<VersoRuntimeConfig>,
+    ) -> Self {
         let context = RuntimeContext {
             windows: Default::default(),
             prefered_theme: Arc::default(),
+            system_theme: Arc::default(),
             event_proxy: event_loop.create_proxy(),
             main_thread: DispatcherMainThreadContext {
                 window_target: event_loop.deref().clone(),
@@ -626,16 +2141,29 @@ impl<T: UserEvent> VersoRuntime<T> {
             next_webview_id: Default::default(),
             next_window_event_id: Default::default(),
             next_webview_event_id: Default::default(),
+            monitors: Arc::new(Mutex::new(event_loop.tauri_available_monitors())),
+            refreshing_monitors: Default::default(),
+            app_id,
+            config: Arc::default(),
         };
+        match config {
+            Some(config) => {
+                let _ = context.config.set(config);
+            }
+            None => PENDING_RUNTIME_CONFIG
+                .with_borrow_mut(|config| *config = Some(context.config.clone())),
+        }
         Self {
             context,
             event_loop,
+            lifecycle: Lifecycle::default(),
         }
     }
 
     fn init_with_builder(
         mut event_loop_builder: EventLoopBuilder<Message<T>>,
         args: RuntimeInitArgs,
+        config: Option<VersoRuntimeConfig>,
     ) -> Self {
         #[cfg(windows)]
         if let Some(hook) = args.msg_hook {
@@ -650,11 +2178,28 @@ impl<T: UserEvent> VersoRuntime<T> {
             target_os = "netbsd",
             target_os = "openbsd"
         ))]
-        if let Some(app_id) = args.app_id {
+        let app_id = args.app_id;
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        if let Some(app_id) = &app_id {
             use tao::platform::unix::EventLoopBuilderExtUnix;
-            event_loop_builder.with_app_id(app_id);
+            event_loop_builder.with_app_id(app_id.clone());
         }
-        Self::init(event_loop_builder.build())
+        #[cfg(not(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        )))]
+        let app_id = None;
+
+        Self::init(event_loop_builder.build(), app_id, config)
     }
 }
 
@@ -667,21 +2212,14 @@ impl<T: UserEvent> Runtime<T> for VersoRuntime<T> {
     /// `args.msg_hook` hooks on the event loop of this process,
     /// this doesn't work for the event loop of versoview instances
     fn new(args: RuntimeInitArgs) -> Result<Self> {
-        let event_loop_builder = EventLoopBuilder::<Message<T>>::with_user_event();
-        Ok(Self::init_with_builder(event_loop_builder, args))
+        Self::create(args, None)
     }
 
     /// `args.msg_hook` hooks on the event loop of this process,
     /// this doesn't work for the event loop of versoview instances
     #[cfg(any(windows, target_os = "linux"))]
     fn new_any_thread(args: RuntimeInitArgs) -> Result<Self> {
-        let mut event_loop_builder = EventLoopBuilder::<Message<T>>::with_user_event();
-        #[cfg(target_os = "linux")]
-        use tao::platform::unix::EventLoopBuilderExtUnix;
-        #[cfg(windows)]
-        use tao::platform::windows::EventLoopBuilderExtWindows;
-        event_loop_builder.with_any_thread(true);
-        Ok(Self::init_with_builder(event_loop_builder, args))
+        Self::create_any_thread(args, None)
     }
 
     fn create_proxy(&self) -> EventProxy<T> {
@@ -724,7 +2262,9 @@ impl<T: UserEvent> Runtime<T> for VersoRuntime<T> {
     }
 
     fn available_monitors(&self) -> Vec<Monitor> {
-        self.event_loop.tauri_available_monitors()
+        let monitors = self.event_loop.tauri_available_monitors();
+        *self.context.monitors.lock().unwrap() = monitors.clone();
+        monitors
     }
 
     fn cursor_position(&self) -> Result<PhysicalPosition<f64>> {
@@ -733,108 +2273,324 @@ impl<T: UserEvent> Runtime<T> for VersoRuntime<T> {
 
     fn set_theme(&self, theme: Option<Theme>) {
         *self.context.prefered_theme.lock().unwrap() = theme;
-        for window in self.context.windows.lock().unwrap().values() {
-            if let Err(error) = window
-                .webview
-                .lock()
-                .unwrap()
-                .set_theme(theme.map(to_verso_theme))
-            {
-                log::error!("Failed to set the theme for webview: {error}");
-            }
-        }
+        self.context.apply_default_theme();
         self.event_loop.set_theme(theme.map(to_tao_theme));
     }
 
-    /// Unsupported, has no effect when called
+    /// Sets the activation policy of this process, the one set before running the app is applied at startup,
+    /// note the versoview processes of the windows have their own
     #[cfg(target_os = "macos")]
     #[cfg_attr(docsrs, doc(cfg(target_os = "macos")))]
-    fn set_activation_policy(&mut self, activation_policy: tauri_runtime::ActivationPolicy) {}
+    fn set_activation_policy(&mut self, activation_policy: tauri_runtime::ActivationPolicy) {
+        use tao::platform::macos::EventLoopExtMacOS;
+        self.event_loop
+            .set_activation_policy(to_tao_activation_policy(activation_policy));
+    }
 
-    /// Unsupported, has no effect when called
+    /// Shows the application (`NSApplication`) of this process,
+    /// note the windows of the versoview processes are not affected
     #[cfg(target_os = "macos")]
     #[cfg_attr(docsrs, doc(cfg(target_os = "macos")))]
-    fn show(&self) {}
+    fn show(&self) {
+        use tao::platform::macos::EventLoopWindowTargetExtMacOS;
+        self.event_loop.show_application();
+    }
 
-    /// Unsupported, has no effect when called
+    /// Hides the application (`NSApplication`) of this process,
+    /// note the windows of the versoview processes are not affected
     #[cfg(target_os = "macos")]
     #[cfg_attr(docsrs, doc(cfg(target_os = "macos")))]
-    fn hide(&self) {}
+    fn hide(&self) {
+        use tao::platform::macos::EventLoopWindowTargetExtMacOS;
+        self.event_loop.hide_application();
+    }
 
-    /// Unsupported, has no effect
+    /// Shows or hides the dock icon of this process, the one set before running the app is applied at startup
     #[cfg(target_os = "macos")]
     #[cfg_attr(docsrs, doc(cfg(target_os = "macos")))]
-    fn set_dock_visibility(&mut self, visible: bool) {}
+    fn set_dock_visibility(&mut self, visible: bool) {
+        use tao::platform::macos::EventLoopExtMacOS;
+        self.event_loop.set_dock_visibility(visible);
+    }
 
     /// Unsupported, has no effect when called
     fn set_device_event_filter(&mut self, filter: DeviceEventFilter) {}
 
-    /// Unsupported, has no effect when called
-    fn run_iteration<F: FnMut(RunEvent<T>)>(&mut self, callback: F) {}
+    /// Processes the pending events and returns, dispatching the same [`RunEvent`]s as [`Runtime::run_return`],
+    /// see [`VersoRuntime::pump_events`]
+    fn run_iteration<F: FnMut(RunEvent<T>) + 'static>(&mut self, callback: F) {
+        self.pump_events(Duration::ZERO, callback);
+    }
 
     fn run<F: FnMut(RunEvent<T>) + 'static>(self, callback: F) {
+        let exit_process = self.context.config().exit_process;
         let exit_code = self.run_return(callback);
-        // std::process::exit(exit_code);
+        if exit_process {
+            std::process::exit(exit_code);
+        }
     }
 
     fn run_return<F: FnMut(RunEvent<T>) + 'static>(mut self, mut callback: F) -> i32 {
-        self.event_loop
-            .run_return(|event, event_loop, control_flow| {
-                if *control_flow != ControlFlow::Exit {
-                    *control_flow = ControlFlow::Wait;
-                }
+        let Self {
+            context,
+            event_loop,
+            lifecycle,
+            ..
+        } = &mut self;
+        let exit_code = event_loop.run_return(|event, event_loop, control_flow| {
+            handle_event_loop(
+                context,
+                lifecycle,
+                &mut callback,
+                event,
+                event_loop,
+                control_flow,
+            );
+        });
+        // Make sure `RunEvent::Exit` is emitted before `Runtime::run` exits the process
+        lifecycle.exit(&mut callback);
+        context.exit_all_versoviews();
+        exit_code
+    }
+}
 
-                match event {
-                    TaoEvent::NewEvents(StartCause::Init) => {
-                        callback(RunEvent::Ready);
-                    }
-                    TaoEvent::NewEvents(StartCause::Poll) => {
-                        callback(RunEvent::Resumed);
-                    }
-                    TaoEvent::MainEventsCleared => {
-                        callback(RunEvent::MainEventsCleared);
-                    }
-                    TaoEvent::LoopDestroyed => {
-                        callback(RunEvent::Exit);
+/// Handles an event from the event loop, shared by [`Runtime::run_return`] and [`Runtime::run_iteration`]
+///
+/// The lifecycle events are mapped as:
+/// - [`StartCause::Init`] to [`RunEvent::Ready`], only the first time
+/// - [`TaoEvent::Resumed`] to [`RunEvent::Resumed`]
+/// - [`TaoEvent::MainEventsCleared`] to [`RunEvent::MainEventsCleared`]
+/// - [`TaoEvent::LoopDestroyed`] to [`RunEvent::Exit`], only the first time
+fn handle_event_loop<T: UserEvent, F: FnMut(RunEvent<T>)>(
+    context: &RuntimeContext<T>,
+    lifecycle: &mut Lifecycle,
+    callback: &mut F,
+    event: TaoEvent<'_, Message<T>>,
+    event_loop: &TaoEventLoopWindowTarget<Message<T>>,
+    control_flow: &mut ControlFlow,
+) {
+    if !matches!(control_flow, ControlFlow::ExitWithCode(_)) {
+        *control_flow = ControlFlow::Wait;
+    }
+
+    // Set up before `RunEvent::Ready` so it's all running when the app gets ready
+    if matches!(event, TaoEvent::NewEvents(StartCause::Init)) && !lifecycle.ready {
+        if context.config().reposition_on_monitor_removed {
+            // There're no display change events for windows in other processes, so poll for them
+            let event_proxy = context.event_proxy.clone();
+            spawn(move || {
+                loop {
+                    sleep(MONITOR_POLL_INTERVAL);
+                    if event_proxy.send_event(Message::CheckMonitors).is_err() {
+                        break;
                     }
-                    TaoEvent::UserEvent(user_event) => match user_event {
-                        Message::Task(p) => p(),
-                        Message::TaskWithEventLoop(p) => p(event_loop),
-                        Message::CloseWindow(id) => {
-                            let should_exit =
-                                self.context
-                                    .handle_close_window_request(&mut callback, id, false);
-                            if should_exit {
-                                *control_flow = ControlFlow::Exit;
-                            }
-                        }
-                        Message::DestroyWindow(id) => {
-                            let should_exit =
-                                self.context
-                                    .handle_close_window_request(&mut callback, id, true);
-                            if should_exit {
-                                *control_flow = ControlFlow::Exit;
-                            }
-                        }
-                        Message::RequestExit(code) => {
-                            let (tx, rx) = channel();
-                            callback(RunEvent::ExitRequested {
-                                code: Some(code),
-                                tx,
-                            });
+                }
+            });
+        }
+        let event_proxy = context.event_proxy.clone();
+        spawn(move || {
+            loop {
+                sleep(SYSTEM_THEME_POLL_INTERVAL);
+                if event_proxy.send_event(Message::CheckSystemTheme).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    lifecycle.handle_event(&event, callback);
+
+    if let TaoEvent::UserEvent(user_event) = event {
+        match user_event {
+            Message::Task(p) => p(),
+            Message::TaskWithEventLoop(p) => p(event_loop),
+            Message::CloseWindow(id) => {
+                context.check_unload_prompt(id);
+            }
+            Message::CloseWindowChecked(id) => {
+                let should_exit = context.handle_close_window_request(callback, id, false);
+                if should_exit {
+                    *control_flow = ControlFlow::Exit;
+                }
+            }
+            Message::DestroyWindow(id) => {
+                let should_exit = context.handle_close_window_request(callback, id, true);
+                if should_exit {
+                    *control_flow = ControlFlow::Exit;
+                }
+            }
+            Message::WindowEvent(id, event) => {
+                context.handle_window_event(callback, id, event);
+            }
+            Message::VersoviewExited(id) => {
+                context.handle_versoview_exited(id);
+            }
+            Message::RestartVersoview(id) => {
+                context.restart_versoview(id);
+            }
+            Message::CheckMonitors => {
+                context.handle_monitors_changed(event_loop);
+            }
+            Message::CheckSystemTheme => {
+                context.handle_system_theme_changed(callback);
+            }
+            Message::RequestExit(code) => {
+                let (tx, rx) = channel();
+                callback(RunEvent::ExitRequested {
+                    code: Some(code),
+                    tx,
+                });
+
+                let recv = rx.try_recv();
+                let should_prevent = matches!(recv, Ok(ExitRequestedEventAction::Prevent));
+
+                if !should_prevent {
+                    *control_flow = ControlFlow::ExitWithCode(code);
+                }
+            }
+            Message::UserEvent(user_event) => callback(RunEvent::UserEvent(user_event)),
+        }
+    }
+}
 
-                            let recv = rx.try_recv();
-                            let should_prevent =
-                                matches!(recv, Ok(ExitRequestedEventAction::Prevent));
+#[cfg(test)]
+mod tests {
+    use tauri_runtime::dpi::PhysicalRect;
 
-                            if !should_prevent {
-                                *control_flow = ControlFlow::Exit;
-                            }
-                        }
-                        Message::UserEvent(user_event) => callback(RunEvent::UserEvent(user_event)),
-                    },
-                    _ => {}
-                }
-            })
+    use super::*;
+
+    fn monitor(name: &str, x: i32, width: u32, height: u32) -> Monitor {
+        Monitor {
+            name: Some(name.to_owned()),
+            size: PhysicalSize::new(width, height),
+            position: PhysicalPosition::new(x, 0),
+            work_area: PhysicalRect {
+                position: PhysicalPosition::new(x, 0),
+                size: PhysicalSize::new(width, height),
+            },
+            scale_factor: 1.0,
+        }
+    }
+
+    #[test]
+    fn unchanged_or_added_monitors_are_not_removals() {
+        let monitors = [
+            monitor("first", 0, 1920, 1080),
+            monitor("second", 1920, 1280, 1024),
+        ];
+        assert!(!is_monitor_removed(&monitors, &monitors));
+        assert!(!is_monitor_removed(
+            &monitors,
+            &[monitors[1].clone(), monitors[0].clone()]
+        ));
+        assert!(!is_monitor_removed(&monitors[..1], &monitors));
+    }
+
+    #[test]
+    fn window_on_a_removed_monitor_is_moved_onto_a_remaining_one() {
+        let first = monitor("first", 0, 1920, 1080);
+        let second = monitor("second", 1920, 1280, 1024);
+        assert!(is_monitor_removed(
+            &[first.clone(), second],
+            &[first.clone()]
+        ));
+        // A window on the second monitor
+        let position = PhysicalPosition::new(2000, 100);
+        let size = PhysicalSize::new(800, 600);
+        assert_eq!(
+            clamp_to_monitors(position, size, &[first.clone()]),
+            Some((first.work_area.position, size))
+        );
+    }
+
+    #[test]
+    fn monitor_with_a_new_resolution_is_a_removal() {
+        let monitors = [monitor("first", 0, 1920, 1080)];
+        assert!(is_monitor_removed(
+            &monitors,
+            &[monitor("first", 0, 1280, 720)]
+        ));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    #[ignore = "needs a display"]
+    fn app_id_from_the_init_args_is_used_for_the_windows() {
+        let runtime = VersoRuntime::<()>::new_any_thread_with_config(
+            RuntimeInitArgs {
+                app_id: Some("org.example.init".to_owned()),
+            },
+            VersoRuntimeConfig::new().app_id("org.example.config"),
+        )
+        .unwrap();
+        assert_eq!(runtime.context.app_id.as_deref(), Some("org.example.init"));
+        // The one from the config takes precedence
+        assert_eq!(runtime.context.window_app_id(), Some("org.example.config"));
+    }
+
+    /// Runs the events through the lifecycle like [`handle_event_loop`] does,
+    /// returns the names of the emitted [`RunEvent`]s
+    fn run_lifecycle(lifecycle: &mut Lifecycle, events: Vec<TaoEvent<'static, ()>>) -> Vec<String> {
+        let mut emitted = Vec::new();
+        let mut callback = |event: RunEvent<()>| emitted.push(format!("{event:?}"));
+        for event in events {
+            lifecycle.handle_event(&event, &mut callback);
+        }
+        emitted
+    }
+
+    #[test]
+    fn poll_does_not_resume() {
+        let mut lifecycle = Lifecycle::default();
+        let emitted = run_lifecycle(
+            &mut lifecycle,
+            vec![
+                TaoEvent::NewEvents(StartCause::Init),
+                TaoEvent::MainEventsCleared,
+                TaoEvent::NewEvents(StartCause::Poll),
+                TaoEvent::MainEventsCleared,
+                TaoEvent::Suspended,
+                TaoEvent::Resumed,
+                TaoEvent::MainEventsCleared,
+            ],
+        );
+        assert_eq!(
+            emitted,
+            [
+                "Ready",
+                "MainEventsCleared",
+                "MainEventsCleared",
+                "Resumed",
+                "MainEventsCleared"
+            ]
+        );
+    }
+
+    #[test]
+    fn ready_and_exit_are_emitted_once_across_iterations() {
+        let mut lifecycle = Lifecycle::default();
+        // Each `Runtime::run_iteration` starts the event loop again, with a new `StartCause::Init`
+        let mut emitted = Vec::new();
+        for _ in 0..3 {
+            emitted.extend(run_lifecycle(
+                &mut lifecycle,
+                vec![
+                    TaoEvent::NewEvents(StartCause::Init),
+                    TaoEvent::MainEventsCleared,
+                ],
+            ));
+        }
+        emitted.extend(run_lifecycle(&mut lifecycle, vec![TaoEvent::LoopDestroyed]));
+        // `Runtime::run_return` makes sure `Exit` is emitted after the event loop returns
+        lifecycle.exit(&mut |event: RunEvent<()>| emitted.push(format!("{event:?}")));
+        assert_eq!(
+            emitted,
+            [
+                "Ready",
+                "MainEventsCleared",
+                "MainEventsCleared",
+                "MainEventsCleared",
+                "Exit"
+            ]
+        );
     }
 }
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use tauri_runtime::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    monitor::Monitor,
+};
+
+use crate::utils::clamp_to_monitors;
+
+/// The state of all the windows, see [`VersoRuntimeHandle::export_session`](crate::VersoRuntimeHandle::export_session)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionState {
+    pub windows: Vec<WindowState>,
+}
+
+/// The state of a window, positions and sizes are in physical pixels
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowState {
+    pub label: String,
+    pub url: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub zoom: f64,
+    pub maximized: bool,
+    pub fullscreen: bool,
+}
+
+impl WindowState {
+    /// Moves the window to the work area of the first monitor
+    /// if its title bar area is not on any of the monitors,
+    /// e.g. the monitor it was on has been disconnected since the session was exported
+    pub fn clamp_to_monitors(&mut self, monitors: &[Monitor]) {
+        let position = PhysicalPosition::new(self.x, self.y);
+        let size = PhysicalSize::new(self.width, self.height);
+        if let Some((position, size)) = clamp_to_monitors(position, size, monitors) {
+            self.x = position.x;
+            self.y = position.y;
+            self.width = size.width;
+            self.height = size.height;
+        }
+    }
+}
@@ -1,3 +1,8 @@
+use tauri_runtime::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    monitor::Monitor,
+};
+
 pub fn to_verso_theme(theme: tauri_utils::Theme) -> verso::Theme {
     match theme {
         tauri_utils::Theme::Dark => verso::Theme::Dark,
@@ -12,9 +17,455 @@ pub fn from_verso_theme(theme: verso::Theme) -> tauri_utils::Theme {
     }
 }
 
+/// Converts a drag and drop event from versoview, the positions are already in physical pixels
+/// relative to the window's content area, and a drop of multiple files has all of their paths
+pub fn from_verso_drag_drop_event(
+    event: verso::DragDropEvent,
+) -> tauri_runtime::window::DragDropEvent {
+    match event {
+        verso::DragDropEvent::Enter { paths, position } => {
+            tauri_runtime::window::DragDropEvent::Enter {
+                paths,
+                position: tauri_runtime::dpi::PhysicalPosition::new(position.x, position.y),
+            }
+        }
+        verso::DragDropEvent::Over { position } => tauri_runtime::window::DragDropEvent::Over {
+            position: tauri_runtime::dpi::PhysicalPosition::new(position.x, position.y),
+        },
+        verso::DragDropEvent::Drop { paths, position } => {
+            tauri_runtime::window::DragDropEvent::Drop {
+                paths,
+                position: tauri_runtime::dpi::PhysicalPosition::new(position.x, position.y),
+            }
+        }
+        verso::DragDropEvent::Leave => tauri_runtime::window::DragDropEvent::Leave,
+    }
+}
+
+/// Reads the system theme without creating a window, `None` if it can't be detected,
+/// on Linux, this must be called on the main thread since it uses GTK
+pub fn system_theme() -> Option<tauri_utils::Theme> {
+    #[cfg(windows)]
+    {
+        use windows::{
+            Win32::System::Registry::{HKEY_CURRENT_USER, RRF_RT_REG_DWORD, RegGetValueW},
+            core::w,
+        };
+        let mut apps_use_light_theme = 0u32;
+        let mut size = size_of::<u32>() as u32;
+        // SAFETY: the value is a `REG_DWORD` (enforced by `RRF_RT_REG_DWORD`), which fits in the `u32` we pass in
+        let result = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                w!("Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize"),
+                w!("AppsUseLightTheme"),
+                RRF_RT_REG_DWORD,
+                None,
+                Some(&mut apps_use_light_theme as *mut u32 as *mut _),
+                Some(&mut size),
+            )
+        };
+        result.is_ok().then_some(if apps_use_light_theme == 0 {
+            tauri_utils::Theme::Dark
+        } else {
+            tauri_utils::Theme::Light
+        })
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use objc2_foundation::{NSUserDefaults, ns_string};
+        // `AppleInterfaceStyle` is only set in dark mode, this is thread safe unlike `NSApp.effectiveAppearance`
+        // SAFETY: `standardUserDefaults` and `stringForKey` are safe to call from any thread
+        let style = unsafe {
+            NSUserDefaults::standardUserDefaults().stringForKey(ns_string!("AppleInterfaceStyle"))
+        };
+        Some(match style {
+            Some(style) if style.to_string().eq_ignore_ascii_case("dark") => {
+                tauri_utils::Theme::Dark
+            }
+            _ => tauri_utils::Theme::Light,
+        })
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        use gtk::prelude::GtkSettingsExt;
+        // Same as how tao detects it
+        let settings = gtk::Settings::default()?;
+        let dark_theme_name = settings.gtk_theme_name().is_some_and(|name| {
+            ["-dark", "-Dark", "-Darker"]
+                .iter()
+                .any(|suffix| name.ends_with(suffix))
+        });
+        Some(
+            if dark_theme_name || settings.is_gtk_application_prefer_dark_theme() {
+                tauri_utils::Theme::Dark
+            } else {
+                tauri_utils::Theme::Light
+            },
+        )
+    }
+
+    #[cfg(not(any(
+        windows,
+        target_os = "macos",
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )))]
+    {
+        None
+    }
+}
+
+pub fn to_verso_cursor_icon(icon: tauri_runtime::window::CursorIcon) -> verso::CursorIcon {
+    use tauri_runtime::window::CursorIcon;
+    match icon {
+        CursorIcon::Hand => verso::CursorIcon::Pointer,
+        CursorIcon::Crosshair => verso::CursorIcon::Crosshair,
+        CursorIcon::Move => verso::CursorIcon::Move,
+        CursorIcon::Text => verso::CursorIcon::Text,
+        CursorIcon::Wait => verso::CursorIcon::Wait,
+        CursorIcon::Help => verso::CursorIcon::Help,
+        CursorIcon::Progress => verso::CursorIcon::Progress,
+        CursorIcon::NotAllowed => verso::CursorIcon::NotAllowed,
+        CursorIcon::ContextMenu => verso::CursorIcon::ContextMenu,
+        CursorIcon::Cell => verso::CursorIcon::Cell,
+        CursorIcon::VerticalText => verso::CursorIcon::VerticalText,
+        CursorIcon::Alias => verso::CursorIcon::Alias,
+        CursorIcon::Copy => verso::CursorIcon::Copy,
+        CursorIcon::NoDrop => verso::CursorIcon::NoDrop,
+        CursorIcon::Grab => verso::CursorIcon::Grab,
+        CursorIcon::Grabbing => verso::CursorIcon::Grabbing,
+        CursorIcon::AllScroll => verso::CursorIcon::AllScroll,
+        CursorIcon::ZoomIn => verso::CursorIcon::ZoomIn,
+        CursorIcon::ZoomOut => verso::CursorIcon::ZoomOut,
+        CursorIcon::EResize => verso::CursorIcon::EResize,
+        CursorIcon::NResize => verso::CursorIcon::NResize,
+        CursorIcon::NeResize => verso::CursorIcon::NeResize,
+        CursorIcon::NwResize => verso::CursorIcon::NwResize,
+        CursorIcon::SResize => verso::CursorIcon::SResize,
+        CursorIcon::SeResize => verso::CursorIcon::SeResize,
+        CursorIcon::SwResize => verso::CursorIcon::SwResize,
+        CursorIcon::WResize => verso::CursorIcon::WResize,
+        CursorIcon::EwResize => verso::CursorIcon::EwResize,
+        CursorIcon::NsResize => verso::CursorIcon::NsResize,
+        CursorIcon::NeswResize => verso::CursorIcon::NeswResize,
+        CursorIcon::NwseResize => verso::CursorIcon::NwseResize,
+        CursorIcon::ColResize => verso::CursorIcon::ColResize,
+        CursorIcon::RowResize => verso::CursorIcon::RowResize,
+        _ => verso::CursorIcon::Default,
+    }
+}
+
 pub fn to_tao_theme(theme: tauri_utils::Theme) -> tao::window::Theme {
     match theme {
         tauri_utils::Theme::Dark => tao::window::Theme::Dark,
         _ => tao::window::Theme::Light,
     }
 }
+
+#[cfg(target_os = "macos")]
+pub fn to_tao_activation_policy(
+    activation_policy: tauri_runtime::ActivationPolicy,
+) -> tao::platform::macos::ActivationPolicy {
+    match activation_policy {
+        tauri_runtime::ActivationPolicy::Accessory => {
+            tao::platform::macos::ActivationPolicy::Accessory
+        }
+        tauri_runtime::ActivationPolicy::Prohibited => {
+            tao::platform::macos::ActivationPolicy::Prohibited
+        }
+        _ => tao::platform::macos::ActivationPolicy::Regular,
+    }
+}
+
+/// Puts the process into a job object that kills it when this process exits (the job handle gets closed),
+/// including when this process crashes or gets killed
+#[cfg(windows)]
+pub fn kill_on_parent_exit(process_id: u32) -> windows::core::Result<()> {
+    use std::sync::OnceLock;
+    use windows::Win32::{
+        Foundation::{CloseHandle, HANDLE},
+        System::{
+            JobObjects::{
+                AssignProcessToJobObject, CreateJobObjectW, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+                JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JobObjectExtendedLimitInformation,
+                SetInformationJobObject,
+            },
+            Threading::{OpenProcess, PROCESS_SET_QUOTA, PROCESS_TERMINATE},
+        },
+    };
+
+    // The job is never closed explicitly, it's closed by the OS when this process exits,
+    // stored as an integer since `HANDLE` is not `Send`
+    static JOB: OnceLock<windows::core::Result<usize>> = OnceLock::new();
+    let job = JOB
+        .get_or_init(|| unsafe {
+            let job = CreateJobObjectW(None, windows::core::PCWSTR::null())?;
+            let mut info = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
+            info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            SetInformationJobObject(
+                job,
+                JobObjectExtendedLimitInformation,
+                &info as *const _ as *const std::ffi::c_void,
+                size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            )?;
+            Ok(job.0 as usize)
+        })
+        .clone()?;
+
+    unsafe {
+        let process = OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, false, process_id)?;
+        let result = AssignProcessToJobObject(HANDLE(job as *mut _), process);
+        let _ = CloseHandle(process);
+        result
+    }
+}
+
+/// Parses a version like `0.0.9` or `v0.0.9-nightly` to `(major, minor, patch)`,
+/// the pre-release and build metadata are ignored
+pub fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.trim().trim_start_matches('v');
+    let version = version.split(['-', '+']).next()?;
+    let mut parts = version.split('.').map(|part| part.parse().ok());
+    let version = (parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(version)
+}
+
+/// Returns the position and size to move a window to the work area of the first monitor
+/// if its title bar area is not on any of the monitors, or [`None`] if it's visible
+pub fn clamp_to_monitors(
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+    monitors: &[Monitor],
+) -> Option<(PhysicalPosition<i32>, PhysicalSize<u32>)> {
+    // Check a point near the top of the window so it can still be dragged around
+    let x = position.x as i64 + (size.width as i64 / 2).min(50);
+    let y = position.y as i64 + (size.height as i64 / 2).min(10);
+    let is_visible = monitors.iter().any(|monitor| {
+        let position = monitor.work_area.position;
+        let size = monitor.work_area.size;
+        (position.x as i64..position.x as i64 + size.width as i64).contains(&x)
+            && (position.y as i64..position.y as i64 + size.height as i64).contains(&y)
+    });
+    if is_visible {
+        return None;
+    }
+    let work_area = monitors.first()?.work_area;
+    Some((
+        work_area.position,
+        PhysicalSize::new(
+            size.width.min(work_area.size.width),
+            size.height.min(work_area.size.height),
+        ),
+    ))
+}
+
+/// Converts a string to a JavaScript string literal (double quoted)
+pub fn to_js_string(string: &str) -> String {
+    let mut js_string = String::with_capacity(string.len() + 2);
+    js_string.push('"');
+    for char in string.chars() {
+        match char {
+            '"' => js_string.push_str("\\\""),
+            '\\' => js_string.push_str("\\\\"),
+            '\n' => js_string.push_str("\\n"),
+            '\r' => js_string.push_str("\\r"),
+            '\t' => js_string.push_str("\\t"),
+            // Line and paragraph separators are not allowed in string literals in older engines
+            '\u{2028}' => js_string.push_str("\\u2028"),
+            '\u{2029}' => js_string.push_str("\\u2029"),
+            // So the literal is also safe to put in a `<script>` element (e.g. `</script>`)
+            '<' => js_string.push_str("\\u003c"),
+            char if char.is_control() => js_string.push_str(&format!("\\u{:04x}", char as u32)),
+            char => js_string.push(char),
+        }
+    }
+    js_string.push('"');
+    js_string
+}
+
+/// Creates a script that adds a `<style>` element with `css` to the document,
+/// waits for the document element if it's run before it's created (e.g. as a user script)
+pub fn css_injection_script(css: &str) -> String {
+    format!(
+        r#"(function () {{
+  const style = document.createElement('style');
+  style.textContent = {css};
+  const inject = () => (document.head || document.documentElement).appendChild(style);
+  if (document.documentElement) {{
+    inject();
+  }} else {{
+    document.addEventListener('DOMContentLoaded', inject, {{ once: true }});
+  }}
+}})();"#,
+        css = to_js_string(css),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use tauri_runtime::dpi::PhysicalRect;
+
+    use super::*;
+
+    /// A monitor with a 40px taskbar at the bottom
+    fn monitor(x: i32, y: i32, width: u32, height: u32) -> Monitor {
+        Monitor {
+            name: None,
+            size: PhysicalSize::new(width, height),
+            position: PhysicalPosition::new(x, y),
+            work_area: PhysicalRect {
+                position: PhysicalPosition::new(x, y),
+                size: PhysicalSize::new(width, height - 40),
+            },
+            scale_factor: 1.0,
+        }
+    }
+
+    #[test]
+    fn visible_window_is_kept() {
+        let monitors = [monitor(0, 0, 1920, 1080), monitor(1920, 0, 1280, 1024)];
+        let size = PhysicalSize::new(800, 600);
+        assert_eq!(
+            clamp_to_monitors(PhysicalPosition::new(100, 100), size, &monitors),
+            None
+        );
+        assert_eq!(
+            clamp_to_monitors(PhysicalPosition::new(2000, 100), size, &monitors),
+            None
+        );
+    }
+
+    #[test]
+    fn window_with_the_title_bar_on_a_monitor_is_kept() {
+        let monitors = [monitor(0, 0, 1920, 1080)];
+        // Mostly off the bottom of the screen, the top left of it can still be dragged
+        assert_eq!(
+            clamp_to_monitors(
+                PhysicalPosition::new(-40, 1000),
+                PhysicalSize::new(800, 600),
+                &monitors
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn window_off_the_monitors_is_moved_to_the_first_one() {
+        let monitors = [monitor(0, 0, 1920, 1080), monitor(-1280, 0, 1280, 1024)];
+        assert_eq!(
+            clamp_to_monitors(
+                PhysicalPosition::new(3000, 200),
+                PhysicalSize::new(800, 600),
+                &monitors
+            ),
+            Some((PhysicalPosition::new(0, 0), PhysicalSize::new(800, 600)))
+        );
+    }
+
+    #[test]
+    fn moved_window_is_shrunk_to_the_work_area() {
+        let monitors = [monitor(0, 0, 1280, 720)];
+        assert_eq!(
+            clamp_to_monitors(
+                PhysicalPosition::new(1920, 0),
+                PhysicalSize::new(1920, 1080),
+                &monitors
+            ),
+            Some((PhysicalPosition::new(0, 0), PhysicalSize::new(1280, 680)))
+        );
+    }
+
+    #[test]
+    fn window_is_kept_without_monitors() {
+        assert_eq!(
+            clamp_to_monitors(
+                PhysicalPosition::new(0, 0),
+                PhysicalSize::new(800, 600),
+                &[]
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn version_is_parsed() {
+        assert_eq!(parse_version("0.0.9"), Some((0, 0, 9)));
+        assert_eq!(parse_version(" 1.12.3\n"), Some((1, 12, 3)));
+    }
+
+    #[test]
+    fn version_prefix_and_suffixes_are_ignored() {
+        assert_eq!(parse_version("v0.0.9"), Some((0, 0, 9)));
+        assert_eq!(parse_version("0.0.9-nightly"), Some((0, 0, 9)));
+        assert_eq!(parse_version("v0.1.0+20250101"), Some((0, 1, 0)));
+    }
+
+    #[test]
+    fn invalid_version_is_rejected() {
+        assert_eq!(parse_version(""), None);
+        assert_eq!(parse_version("unknown"), None);
+        assert_eq!(parse_version("0.9"), None);
+        assert_eq!(parse_version("0.0.9.1"), None);
+        assert_eq!(parse_version("0.x.9"), None);
+    }
+
+    #[test]
+    fn js_string_is_quoted() {
+        assert_eq!(to_js_string("hello"), r#""hello""#);
+        assert_eq!(to_js_string(""), r#""""#);
+    }
+
+    #[test]
+    fn js_string_escapes_quotes_and_backslashes() {
+        assert_eq!(to_js_string(r#"say "hi" \o/"#), r#""say \"hi\" \\o/""#);
+    }
+
+    #[test]
+    fn js_string_escapes_line_breaks_and_control_characters() {
+        assert_eq!(
+            to_js_string("a\nb\rc\td\u{2028}e\u{2029}f\u{0}"),
+            r#""a\nb\rc\td\u2028e\u2029f\u0000""#
+        );
+    }
+
+    #[test]
+    fn js_string_can_not_close_a_script_element() {
+        assert_eq!(to_js_string("</script>"), r#""\u003c/script>""#);
+    }
+
+    #[test]
+    fn js_string_keeps_non_ascii_characters() {
+        assert_eq!(to_js_string("héllo 世界 🦀"), r#""héllo 世界 🦀""#);
+    }
+
+    #[test]
+    fn css_is_injected_as_a_string_literal() {
+        let script = css_injection_script("body::after { content: \"</style>\"; }");
+        assert!(
+            script
+                .contains(r#"style.textContent = "body::after { content: \"\u003c/style>\"; }";"#)
+        );
+    }
+
+    #[test]
+    fn css_injection_waits_for_the_document_element() {
+        let script = css_injection_script("body { margin: 0; }");
+        assert!(script.contains("if (document.documentElement) {"));
+        assert!(
+            script
+                .contains("document.addEventListener('DOMContentLoaded', inject, { once: true });")
+        );
+    }
+}
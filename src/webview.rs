@@ -1,5 +1,7 @@
 #![allow(unused_variables)]
 
+use serde::Serialize;
+use tauri::image::Image;
 use tauri_runtime::{
     Error, Result, UserEvent, WebviewDispatch, WebviewEventId,
     dpi::{PhysicalPosition, PhysicalSize, Position, Size},
@@ -9,18 +11,243 @@ use url::Url;
 use verso::VersoviewController;
 
 use std::{
+    collections::HashMap,
     fmt::{self, Debug},
-    sync::{Arc, Mutex},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU32, Ordering},
+    },
 };
 
-use crate::{RuntimeContext, VersoRuntime};
+use crate::{
+    KeyEvent, MouseEvent, RuntimeContext, VersoRuntime,
+    utils::{css_injection_script, to_js_string},
+    window::FullscreenState,
+};
+
+/// The error from [`VersoWebviewDispatcher::try_navigate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NavigateError {
+    /// Failed to send the navigation to versoview, e.g. the process has exited
+    FailedToSendMessage,
+    /// Versoview rejected the navigation, with the reason
+    Rejected(String),
+}
+
+impl fmt::Display for NavigateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FailedToSendMessage => f.write_str("failed to send message to the webview"),
+            Self::Rejected(reason) => write!(f, "navigation rejected: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for NavigateError {}
+
+/// The error from [`VersoWebviewDispatcher::eval_script_with_callback`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalScriptError {
+    /// Failed to send the script to versoview or get the result back, e.g. the process has exited
+    FailedToSendMessage,
+    /// The script threw, with the message of the exception
+    Exception(String),
+}
+
+impl fmt::Display for EvalScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FailedToSendMessage => f.write_str("failed to send message to the webview"),
+            Self::Exception(message) => write!(f, "script threw an exception: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for EvalScriptError {}
+
+/// The error from [`VersoWebviewDispatcher::capture_element`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaptureElementError {
+    /// Failed to send the request to versoview, e.g. the process has exited
+    FailedToSendMessage,
+    /// The selector is not a valid CSS selector
+    InvalidSelector(String),
+    /// No element in the page matches the selector
+    NoMatch(String),
+}
+
+impl fmt::Display for CaptureElementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FailedToSendMessage => f.write_str("failed to send message to the webview"),
+            Self::InvalidSelector(selector) => write!(f, "invalid selector: {selector}"),
+            Self::NoMatch(selector) => write!(f, "no element matches the selector: {selector}"),
+        }
+    }
+}
+
+impl std::error::Error for CaptureElementError {}
+
+/// A location on earth for [`VersoWebviewDispatcher::set_geolocation_override`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinates {
+    /// In decimal degrees
+    pub latitude: f64,
+    /// In decimal degrees
+    pub longitude: f64,
+    /// The accuracy of the position in meters
+    pub accuracy: f64,
+}
+
+/// The options for [`VersoWebviewDispatcher::find_in_page`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FindOptions {
+    /// Only match text with the same case as the query, defaults to `false`
+    pub case_sensitive: bool,
+    /// Go to the first match after the last one (or the last match before the first one when searching backwards),
+    /// defaults to `true`
+    pub wrap_around: bool,
+    /// Go to the previous match instead of the next one, defaults to `false`
+    pub backwards: bool,
+}
+
+impl Default for FindOptions {
+    fn default() -> Self {
+        Self {
+            case_sensitive: false,
+            wrap_around: true,
+            backwards: false,
+        }
+    }
+}
+
+/// The result of [`VersoWebviewDispatcher::find_in_page`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FindResult {
+    /// The number of matches in the page
+    pub match_count: u32,
+    /// The index of the highlighted match, from 0, [`None`] if there's no match
+    pub active_match_index: Option<u32>,
+}
+
+/// The user scripts added after creation by name, in the order they're added
+pub(crate) type UserScripts = Vec<(String, String)>;
+
+pub type LoadProgressHandler = Arc<dyn Fn(f64) + Send + Sync>;
+
+/// The load progress of a webview and the listeners to it
+#[derive(Default)]
+pub(crate) struct LoadProgress {
+    /// In percentage, from 0 to 100
+    progress: f64,
+    listeners: HashMap<WebviewEventId, LoadProgressHandler>,
+}
+
+impl LoadProgress {
+    /// Resets the progress to 0 when a new load starts and notifies the listeners
+    pub(crate) fn start(load_progress: &Mutex<Self>) {
+        Self::set(load_progress, 0.0);
+    }
+
+    /// Updates the progress and notifies the listeners,
+    /// the progress only goes forward until the next load starts, see [`LoadProgress::start`]
+    pub(crate) fn update(load_progress: &Mutex<Self>, progress: f64) {
+        let progress = progress.clamp(0.0, 100.0);
+        if progress < load_progress.lock().unwrap().progress {
+            return;
+        }
+        Self::set(load_progress, progress);
+    }
+
+    fn set(load_progress: &Mutex<Self>, progress: f64) {
+        let listeners = {
+            let mut load_progress = load_progress.lock().unwrap();
+            load_progress.progress = progress;
+            load_progress
+                .listeners
+                .values()
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        for listener in listeners {
+            listener(progress);
+        }
+    }
+}
+
+/// The events from [`VersoWebviewDispatcher::emit_to_webview`] emitted while the page is loading,
+/// they're kept until it finishes loading so they're not lost
+#[derive(Default)]
+pub(crate) struct PendingEvents {
+    loaded: bool,
+    scripts: Vec<String>,
+}
+
+impl PendingEvents {
+    /// Keeps the events emitted from now on until [`PendingEvents::finish`] when a new load starts
+    pub(crate) fn start(&mut self) {
+        self.loaded = false;
+    }
+
+    /// Dispatches the events kept while loading to the loaded page, the ones emitted after this are dispatched right away
+    pub(crate) fn finish(pending_events: &Mutex<Self>, webview: &Mutex<VersoviewController>) {
+        let mut pending_events = pending_events.lock().unwrap();
+        pending_events.loaded = true;
+        let webview = webview.lock().unwrap();
+        for script in pending_events.scripts.drain(..) {
+            if let Err(error) = webview.execute_script(script) {
+                log::error!(
+                    "Failed to dispatch an event emitted while the page was loading: {error}"
+                );
+            }
+        }
+    }
+
+    /// Dispatches the event if the page is loaded, or keeps it until the page finishes loading,
+    /// the lock is held while dispatching so the events are dispatched in the order they're emitted
+    fn emit(
+        pending_events: &Mutex<Self>,
+        webview: &Mutex<VersoviewController>,
+        script: String,
+    ) -> Result<()> {
+        let mut pending_events = pending_events.lock().unwrap();
+        if !pending_events.loaded {
+            pending_events.scripts.push(script);
+            return Ok(());
+        }
+        webview
+            .lock()
+            .unwrap()
+            .execute_script(script)
+            .map_err(|_| Error::FailedToSendMessage)
+    }
+}
+
+/// Creates a script that dispatches a [`CustomEvent`](https://developer.mozilla.org/docs/Web/API/CustomEvent)
+/// named `event` on `window` with the JSON `payload` parsed as its `detail`
+fn emit_script(event: &str, payload: &str) -> String {
+    format!(
+        "window.dispatchEvent(new CustomEvent({event}, {{ detail: JSON.parse({payload}) }}))",
+        event = to_js_string(event),
+        payload = to_js_string(payload),
+    )
+}
 
 /// The Tauri [`WebviewDispatch`] for [`VersoRuntime`].
 #[derive(Clone)]
 pub struct VersoWebviewDispatcher<T: UserEvent> {
     pub(crate) id: u32,
+    /// The window this webview is in, every window has exactly one webview for now
+    pub(crate) window_id: WindowId,
     pub(crate) context: RuntimeContext<T>,
     pub(crate) webview: Arc<Mutex<VersoviewController>>,
+    pub(crate) load_progress: Arc<Mutex<LoadProgress>>,
+    pub(crate) process_id: Arc<AtomicU32>,
+    pub(crate) zoom: Arc<Mutex<f64>>,
+    pub(crate) user_scripts: Arc<Mutex<UserScripts>>,
+    /// The fullscreen state of the window, for forwarding the geometry changes to the window
+    pub(crate) fullscreen: Arc<Mutex<FullscreenState>>,
+    pub(crate) pending_events: Arc<Mutex<PendingEvents>>,
 }
 
 impl<T: UserEvent> Debug for VersoWebviewDispatcher<T> {
@@ -33,6 +260,441 @@ impl<T: UserEvent> Debug for VersoWebviewDispatcher<T> {
     }
 }
 
+impl<T: UserEvent> VersoWebviewDispatcher<T> {
+    /// Returns the OS process id of the versoview process of this webview,
+    /// or [`None`] if the process has exited
+    ///
+    /// This doesn't communicate with versoview, so it never blocks
+    pub fn process_id(&self) -> Option<u32> {
+        match self.process_id.load(Ordering::Relaxed) {
+            0 => None,
+            process_id => Some(process_id),
+        }
+    }
+
+    /// Returns the load progress of the current page in percentage, from 0 to 100
+    pub fn load_progress(&self) -> Result<f64> {
+        Ok(self.load_progress.lock().unwrap().progress)
+    }
+
+    /// Registers a listener to be called with the load progress in percentage (from 0 to 100)
+    /// periodically during a page load, with 0 when a load starts and with 100 when it finishes
+    ///
+    /// Use [`VersoWebviewDispatcher::remove_load_progress_listener`] with the returned id to remove it
+    pub fn on_load_progress<F: Fn(f64) + Send + Sync + 'static>(&self, f: F) -> WebviewEventId {
+        let id = self.context.next_webview_event_id();
+        self.load_progress
+            .lock()
+            .unwrap()
+            .listeners
+            .insert(id, Arc::new(f));
+        id
+    }
+
+    /// Removes a listener registered with [`VersoWebviewDispatcher::on_load_progress`],
+    /// returns `false` if there's no listener with this id
+    pub fn remove_load_progress_listener(&self, id: WebviewEventId) -> bool {
+        self.load_progress
+            .lock()
+            .unwrap()
+            .listeners
+            .remove(&id)
+            .is_some()
+    }
+
+    /// Enables or disables the spellchecker for editable content (`<textarea>`, `<input>` and `contenteditable`),
+    /// it's enabled by default to align with the wry runtime
+    pub fn set_spellcheck(&self, enabled: bool) -> Result<()> {
+        self.webview
+            .lock()
+            .unwrap()
+            .set_spellcheck(enabled)
+            .map_err(|_| Error::FailedToSendMessage)?;
+        Ok(())
+    }
+
+    /// Returns if the spellchecker is enabled for editable content
+    pub fn is_spellcheck_enabled(&self) -> Result<bool> {
+        self.webview
+            .lock()
+            .unwrap()
+            .is_spellcheck_enabled()
+            .map_err(|_| Error::FailedToSendMessage)
+    }
+
+    /// Mutes or unmutes all the audio of the webview, including media elements and Web Audio,
+    /// independent of the system volume, this stays across navigations
+    pub fn set_muted(&self, muted: bool) -> Result<()> {
+        self.webview
+            .lock()
+            .unwrap()
+            .set_muted(muted)
+            .map_err(|_| Error::FailedToSendMessage)?;
+        Ok(())
+    }
+
+    /// Returns if the audio of the webview is muted
+    pub fn is_muted(&self) -> Result<bool> {
+        self.webview
+            .lock()
+            .unwrap()
+            .is_muted()
+            .map_err(|_| Error::FailedToSendMessage)
+    }
+
+    /// Stops loading the current page, like the stop button of a browser,
+    /// the parts already loaded stay, has no effect if the page is already loaded
+    pub fn stop_loading(&self) -> Result<()> {
+        self.webview
+            .lock()
+            .unwrap()
+            .stop_loading()
+            .map_err(|_| Error::FailedToSendMessage)?;
+        Ok(())
+    }
+
+    /// Returns if the webview is loading a page, e.g. for showing a stop button instead of a reload button
+    pub fn is_loading(&self) -> Result<bool> {
+        self.webview
+            .lock()
+            .unwrap()
+            .is_loading()
+            .map_err(|_| Error::FailedToSendMessage)
+    }
+
+    /// Reloads the current page bypassing the cache, so every resource is requested again,
+    /// including the ones from custom protocols and the ones cached by
+    /// [`VersoRuntimeConfig::protocol_response_cache`](crate::VersoRuntimeConfig::protocol_response_cache), use [`WebviewDispatch::reload`] for a normal reload
+    pub fn reload_ignoring_cache(&self) -> Result<()> {
+        self.webview
+            .lock()
+            .unwrap()
+            .reload_ignoring_cache()
+            .map_err(|_| Error::FailedToSendMessage)?;
+        Ok(())
+    }
+
+    /// Navigates to the URL, returns [`NavigateError::Rejected`] with the reason
+    /// if versoview rejects the navigation (e.g. an unsupported scheme)
+    ///
+    /// This only tells if the navigation started, use [`VersoWindowBuilder::on_load_event`](crate::VersoWindowBuilder::on_load_event)
+    /// to know if the page loaded or failed to load
+    pub fn try_navigate(&self, url: Url) -> std::result::Result<(), NavigateError> {
+        self.webview
+            .lock()
+            .unwrap()
+            .navigate_with_result(url)
+            .map_err(|_| NavigateError::FailedToSendMessage)?
+            .map_err(NavigateError::Rejected)
+    }
+
+    /// Goes back to the previous page in the webview's navigation history,
+    /// has no effect if there's no previous page
+    ///
+    /// Note this operates on the webview's navigation history,
+    /// client side routing in the app only shows up here if it uses the `History` API
+    pub fn go_back(&self) -> Result<()> {
+        self.webview
+            .lock()
+            .unwrap()
+            .go_back()
+            .map_err(|_| Error::FailedToSendMessage)?;
+        Ok(())
+    }
+
+    /// Goes forward to the next page in the webview's navigation history,
+    /// has no effect if there's no next page
+    ///
+    /// Note this operates on the webview's navigation history,
+    /// client side routing in the app only shows up here if it uses the `History` API
+    pub fn go_forward(&self) -> Result<()> {
+        self.webview
+            .lock()
+            .unwrap()
+            .go_forward()
+            .map_err(|_| Error::FailedToSendMessage)?;
+        Ok(())
+    }
+
+    /// Returns if there's a previous page in the webview's navigation history to go back to
+    pub fn can_go_back(&self) -> Result<bool> {
+        self.webview
+            .lock()
+            .unwrap()
+            .can_go_back()
+            .map_err(|_| Error::FailedToSendMessage)
+    }
+
+    /// Returns if there's a next page in the webview's navigation history to go forward to
+    pub fn can_go_forward(&self) -> Result<bool> {
+        self.webview
+            .lock()
+            .unwrap()
+            .can_go_forward()
+            .map_err(|_| Error::FailedToSendMessage)
+    }
+
+    /// Sets whether to run JavaScript in the pages, this takes effect on the next navigation,
+    /// see [`VersoWindowBuilder::javascript_enabled`](crate::VersoWindowBuilder::javascript_enabled)
+    ///
+    /// Note the Tauri APIs don't work in the page when this is disabled, and scripts from
+    /// [`WebviewDispatch::eval_script`] won't run either
+    pub fn set_javascript_enabled(&self, enabled: bool) -> Result<()> {
+        if !enabled {
+            log::warn!("JavaScript is disabled, the Tauri APIs won't work in this webview");
+        }
+        self.webview
+            .lock()
+            .unwrap()
+            .set_javascript_enabled(enabled)
+            .map_err(|_| Error::FailedToSendMessage)?;
+        Ok(())
+    }
+
+    /// Sets whether to block loading `http` resources (e.g. images and scripts) on `https` pages,
+    /// this takes effect on the resources loaded after this
+    pub fn set_block_mixed_content(&self, block: bool) -> Result<()> {
+        self.webview
+            .lock()
+            .unwrap()
+            .set_block_mixed_content(block)
+            .map_err(|_| Error::FailedToSendMessage)?;
+        Ok(())
+    }
+
+    /// Overrides the `User-Agent` of the webview for the requests after this,
+    /// including `navigator.userAgent` of the pages loaded after this,
+    /// pass in [`None`] to go back to the one the webview was created with
+    ///
+    /// To use a different `User-Agent` for a single navigation,
+    /// set it before calling [`WebviewDispatch::navigate`] and set it back to [`None`] once the page is loaded
+    pub fn set_user_agent(&self, user_agent: Option<String>) -> Result<()> {
+        self.webview
+            .lock()
+            .unwrap()
+            .set_user_agent(user_agent)
+            .map_err(|_| Error::FailedToSendMessage)?;
+        Ok(())
+    }
+
+    /// Makes the Geolocation API (e.g. `navigator.geolocation.getCurrentPosition`) of the page
+    /// return the given coordinates instead of the real location, useful for testing or kiosks,
+    /// pass in [`None`] to use the real location again
+    ///
+    /// The geolocation permission is granted to the page while an override is set
+    pub fn set_geolocation_override(&self, coordinates: Option<Coordinates>) -> Result<()> {
+        self.webview
+            .lock()
+            .unwrap()
+            .set_geolocation_override(coordinates.map(|coordinates| {
+                verso::GeolocationCoordinates {
+                    latitude: coordinates.latitude,
+                    longitude: coordinates.longitude,
+                    accuracy: coordinates.accuracy,
+                }
+            }))
+            .map_err(|_| Error::FailedToSendMessage)?;
+        Ok(())
+    }
+
+    /// Adds a script to run at the start of every page loaded after this, before the page's own scripts,
+    /// this replaces the script with the same `name` if there's one
+    ///
+    /// This takes effect on the next navigation, use [`WebviewDispatch::eval_script`] to run it on the current page
+    pub fn add_user_script(
+        &self,
+        name: impl Into<String>,
+        script: impl Into<String>,
+    ) -> Result<()> {
+        let name = name.into();
+        let script = script.into();
+        let mut user_scripts = self.user_scripts.lock().unwrap();
+        self.webview
+            .lock()
+            .unwrap()
+            .add_user_script(name.clone(), script.clone())
+            .map_err(|_| Error::FailedToSendMessage)?;
+        match user_scripts
+            .iter_mut()
+            .find(|(existing, _)| *existing == name)
+        {
+            Some((_, existing)) => *existing = script,
+            None => user_scripts.push((name, script)),
+        }
+        Ok(())
+    }
+
+    /// Removes the user script added with [`VersoWebviewDispatcher::add_user_script`] by its name,
+    /// this takes effect on the next navigation, returns `false` if there's no script with this name
+    ///
+    /// Note the initialization scripts the webview is created with can't be removed
+    pub fn remove_user_script(&self, name: &str) -> Result<bool> {
+        let mut user_scripts = self.user_scripts.lock().unwrap();
+        let Some(index) = user_scripts
+            .iter()
+            .position(|(existing, _)| existing == name)
+        else {
+            return Ok(false);
+        };
+        self.webview
+            .lock()
+            .unwrap()
+            .remove_user_script(name)
+            .map_err(|_| Error::FailedToSendMessage)?;
+        user_scripts.remove(index);
+        Ok(true)
+    }
+
+    /// Renders the current page to an RGBA image, of the visible viewport,
+    /// or the whole page including the parts scrolled out of view if `full_page` is `true`
+    ///
+    /// The image is in physical pixels, so it's scaled by the window's scale factor
+    pub fn capture_page(&self, full_page: bool) -> Result<Image<'static>> {
+        let image = self
+            .webview
+            .lock()
+            .unwrap()
+            .capture_page(full_page)
+            .map_err(|_| Error::FailedToSendMessage)?;
+        Ok(Image::new_owned(image.rgba, image.width, image.height))
+    }
+
+    /// Searches the current page for `query` and highlights the next match (or the previous one with
+    /// [`FindOptions::backwards`]), calling this again with the same query moves to the next match
+    ///
+    /// The highlights stay until [`VersoWebviewDispatcher::stop_find`] is called or the page navigates
+    pub fn find_in_page(&self, query: &str, options: FindOptions) -> Result<FindResult> {
+        let result = self
+            .webview
+            .lock()
+            .unwrap()
+            .find_in_page(
+                query.to_owned(),
+                verso::FindOptions {
+                    case_sensitive: options.case_sensitive,
+                    wrap_around: options.wrap_around,
+                    backwards: options.backwards,
+                },
+            )
+            .map_err(|_| Error::FailedToSendMessage)?;
+        Ok(FindResult {
+            match_count: result.match_count,
+            active_match_index: result.active_match_index,
+        })
+    }
+
+    /// Clears the highlights of [`VersoWebviewDispatcher::find_in_page`],
+    /// the active match stays selected so the user can copy it
+    pub fn stop_find(&self) -> Result<()> {
+        self.webview
+            .lock()
+            .unwrap()
+            .stop_find()
+            .map_err(|_| Error::FailedToSendMessage)?;
+        Ok(())
+    }
+
+    /// Renders the first element matching the CSS `selector` to an RGBA image, e.g. a chart for exporting,
+    /// the image covers the element's border box, including the parts scrolled out of view
+    ///
+    /// The image is in physical pixels, so it's the element's size scaled by the window's scale factor
+    pub fn capture_element(
+        &self,
+        selector: &str,
+    ) -> std::result::Result<Image<'static>, CaptureElementError> {
+        let image = self
+            .webview
+            .lock()
+            .unwrap()
+            .capture_element(selector.to_owned())
+            .map_err(|_| CaptureElementError::FailedToSendMessage)?
+            .map_err(|error| match error {
+                verso::CaptureElementError::InvalidSelector => {
+                    CaptureElementError::InvalidSelector(selector.to_owned())
+                }
+                verso::CaptureElementError::NoMatch => {
+                    CaptureElementError::NoMatch(selector.to_owned())
+                }
+            })?;
+        Ok(Image::new_owned(image.rgba, image.width, image.height))
+    }
+
+    /// Injects a stylesheet into the current page, the stylesheet is gone after navigating to another page,
+    /// use [`VersoWindowBuilder::inject_css`](crate::VersoWindowBuilder::inject_css) for every page
+    ///
+    /// If the document is not created yet, the stylesheet is added once it's loaded
+    pub fn inject_css(&self, css: &str) -> Result<()> {
+        self.eval_script(css_injection_script(css))
+    }
+
+    /// Returns the port of the devtools server of this webview, or [`None`] if it's not running
+    pub fn devtools_port(&self) -> Result<Option<u16>> {
+        self.webview
+            .lock()
+            .unwrap()
+            .devtools_port()
+            .map_err(|_| Error::FailedToSendMessage)
+    }
+
+    /// Evaluates the script in the page and calls `callback` with the result of its last expression,
+    /// values that can't be serialized to JSON (e.g. functions and DOM nodes) are returned as `null`,
+    /// and [`EvalScriptError::Exception`] is returned with the message if the script throws
+    ///
+    /// This returns right after sending the script, the callback is called on a background thread
+    /// once versoview replies, so it's safe to use on the main thread,
+    /// if sending the script fails, [`Error::FailedToSendMessage`] is returned and the callback is not called
+    pub fn eval_script_with_callback(
+        &self,
+        script: impl Into<String>,
+        callback: impl FnOnce(std::result::Result<serde_json::Value, EvalScriptError>) + Send + 'static,
+    ) -> Result<()> {
+        self.webview
+            .lock()
+            .unwrap()
+            .execute_script_with_callback(script.into(), move |result| {
+                callback(match result {
+                    Ok(value) => Ok(value.unwrap_or(serde_json::Value::Null)),
+                    Err(verso::ScriptError::Exception(message)) => {
+                        Err(EvalScriptError::Exception(message))
+                    }
+                    Err(_) => Err(EvalScriptError::FailedToSendMessage),
+                });
+            })
+            .map_err(|_| Error::FailedToSendMessage)?;
+        Ok(())
+    }
+
+    /// Emits a [`CustomEvent`](https://developer.mozilla.org/docs/Web/API/CustomEvent) named `event` on `window` in the page,
+    /// with the payload serialized to JSON and parsed back as the event's `detail`
+    ///
+    /// ```js
+    /// window.addEventListener('progress-update', (event) => console.log(event.detail))
+    /// ```
+    ///
+    /// The event is only received by the current page, if it's still loading,
+    /// the event is kept and dispatched once it finishes loading
+    pub fn emit_to_webview<S: Serialize>(&self, event: &str, payload: &S) -> Result<()> {
+        let payload = serde_json::to_string(payload)?;
+        PendingEvents::emit(
+            &self.pending_events,
+            &self.webview,
+            emit_script(event, &payload),
+        )
+    }
+
+    /// Dispatches a synthetic mouse event to the element at the event's position in the page,
+    /// useful for UI automation without OS level input injection
+    pub fn send_mouse_event(&self, event: MouseEvent) -> Result<()> {
+        self.eval_script(event.to_script())
+    }
+
+    /// Dispatches a synthetic keyboard event to the focused element in the page,
+    /// useful for UI automation without OS level input injection
+    pub fn send_key_event(&self, event: KeyEvent) -> Result<()> {
+        self.eval_script(event.to_script())
+    }
+}
+
 impl<T: UserEvent> WebviewDispatch<T> for VersoWebviewDispatcher<T> {
     type Runtime = VersoRuntime<T>;
 
@@ -50,8 +712,22 @@ impl<T: UserEvent> WebviewDispatch<T> for VersoWebviewDispatcher<T> {
         Ok(())
     }
 
-    /// Unsupported, has no effect when called
+    /// Sets the zoom level of the web content, like the browser zoom
+    ///
+    /// Note this is different from the DPI scale factor of the window,
+    /// this only scales the page content, it doesn't change the window's
+    /// [`scale_factor`](tauri_runtime::WindowDispatch::scale_factor),
+    /// [`inner_size`](tauri_runtime::WindowDispatch::inner_size) or any other window geometry,
+    /// the page sees the zoom through `window.devicePixelRatio` and a smaller viewport in CSS pixels
+    ///
+    /// The zoom level is kept across navigations in this webview
     fn set_zoom(&self, scale_factor: f64) -> Result<()> {
+        self.webview
+            .lock()
+            .unwrap()
+            .set_zoom(scale_factor)
+            .map_err(|_| Error::FailedToSendMessage)?;
+        *self.zoom.lock().unwrap() = scale_factor;
         Ok(())
     }
 
@@ -81,8 +757,20 @@ impl<T: UserEvent> WebviewDispatch<T> for VersoWebviewDispatcher<T> {
         })
     }
 
+    /// Returns the position of the webview, since the webview fills the whole window,
+    /// this is the window's [`inner_position`](tauri_runtime::WindowDispatch::inner_position)
+    ///
+    /// ## Platform-specific
+    ///
+    /// **Wayland**: always return `PhysicalPosition { x: 0, y: 0 }`
     fn position(&self) -> Result<PhysicalPosition<i32>> {
-        Ok(PhysicalPosition { x: 0, y: 0 })
+        Ok(self
+            .webview
+            .lock()
+            .unwrap()
+            .get_inner_position()
+            .map_err(|_| Error::FailedToSendMessage)?
+            .unwrap_or_default())
     }
 
     fn size(&self) -> Result<PhysicalSize<u32>> {
@@ -95,18 +783,36 @@ impl<T: UserEvent> WebviewDispatch<T> for VersoWebviewDispatcher<T> {
         Ok(size)
     }
 
+    /// Navigates to the URL, see [`VersoWebviewDispatcher::try_navigate`] to tell apart
+    /// versoview rejecting the navigation from failing to communicate with it,
+    /// both are [`Error::FailedToSendMessage`] here
     fn navigate(&self, url: Url) -> Result<()> {
-        self.webview
-            .lock()
-            .unwrap()
-            .navigate(url)
-            .map_err(|_| Error::FailedToSendMessage)?;
-        Ok(())
+        self.try_navigate(url).map_err(|error| {
+            if let NavigateError::Rejected(reason) = &error {
+                log::error!("Navigation rejected by versoview: {reason}");
+            }
+            Error::FailedToSendMessage
+        })
     }
 
-    /// Unsupported, has no effect when called
+    /// Opens the platform's print dialog for the current page
+    ///
+    /// Returns [`Error::FailedToSendMessage`] if versoview reports printing is not available,
+    /// calling this while a print dialog is already open has no effect
     fn print(&self) -> Result<()> {
-        Ok(())
+        let status = self
+            .webview
+            .lock()
+            .unwrap()
+            .print()
+            .map_err(|_| Error::FailedToSendMessage)?;
+        match status {
+            verso::PrintStatus::Started | verso::PrintStatus::AlreadyPrinting => Ok(()),
+            verso::PrintStatus::Unavailable => {
+                log::error!("Printing is not available in versoview");
+                Err(Error::FailedToSendMessage)
+            }
+        }
     }
 
     /// Unsupported, has no effect when called,
@@ -116,37 +822,51 @@ impl<T: UserEvent> WebviewDispatch<T> for VersoWebviewDispatcher<T> {
         Ok(())
     }
 
-    /// Unsupported, has no effect when called,
-    /// the versoview controls both the webview and the window
-    /// use the method from the parent window instead
+    /// Sets the bounds of the window, since the webview fills the whole window,
+    /// see [`WebviewDispatch::set_size`] and [`WebviewDispatch::set_position`]
     fn set_bounds(&self, bounds: tauri_runtime::dpi::Rect) -> Result<()> {
-        Ok(())
+        self.set_size(bounds.size)?;
+        self.set_position(bounds.position)
     }
 
-    /// Unsupported, has no effect when called,
-    /// the versoview controls both the webview and the window
-    /// use the method from the parent window instead
-    fn set_size(&self, _size: Size) -> Result<()> {
-        Ok(())
+    /// Sets the inner size of the window, since the webview fills the whole window,
+    /// same as [`WindowDispatch::set_size`](tauri_runtime::WindowDispatch::set_size) of the parent window
+    fn set_size(&self, size: Size) -> Result<()> {
+        FullscreenState::set_size(&self.fullscreen, &self.webview, size)
     }
 
-    /// Unsupported, has no effect when called,
-    /// the versoview controls both the webview and the window
-    /// use the method from the parent window instead
-    fn set_position(&self, _position: Position) -> Result<()> {
-        Ok(())
+    /// Sets the outer position of the window, since the webview fills the whole window,
+    /// same as [`WindowDispatch::set_position`](tauri_runtime::WindowDispatch::set_position) of the parent window
+    fn set_position(&self, position: Position) -> Result<()> {
+        FullscreenState::set_position(&self.fullscreen, &self.webview, position)
     }
 
-    /// Unsupported, has no effect when called,
-    /// the versoview controls both the webview and the window
-    /// use the method from the parent window instead
+    /// Focuses the window, since the webview fills the whole window
     fn set_focus(&self) -> Result<()> {
+        self.webview
+            .lock()
+            .unwrap()
+            .focus()
+            .map_err(|_| Error::FailedToSendMessage)?;
         Ok(())
     }
 
-    /// Unsupported, has no effect when called
+    /// Moving a webview to another window is not supported,
+    /// since the webview of each window lives in its own versoview process,
+    /// this only succeeds if `window_id` is the window this webview is already in,
+    /// and fails with [`Error::WindowNotFound`] if the window doesn't exist
+    /// or [`Error::FailedToSendMessage`] if it's another window
     fn reparent(&self, window_id: WindowId) -> Result<()> {
-        Ok(())
+        if window_id == self.window_id {
+            return Ok(());
+        }
+        if !self.context.has_window(window_id) {
+            return Err(Error::WindowNotFound);
+        }
+        log::error!(
+            "Can't move the webview to another window, the webview of each window lives in its own versoview process"
+        );
+        Err(Error::FailedToSendMessage)
     }
 
     /// Unsupported, has no effect when called
@@ -154,22 +874,35 @@ impl<T: UserEvent> WebviewDispatch<T> for VersoWebviewDispatcher<T> {
         Ok(())
     }
 
-    /// Unsupported, has no effect when called
+    /// Clears the cookies, HTTP cache, local and session storage and IndexedDB of the webview's profile,
+    /// including the ones stored in the webview's `data_directory` if it's set,
+    /// this waits for versoview to finish clearing them, so it's safe to navigate right after it returns
     fn clear_all_browsing_data(&self) -> Result<()> {
+        self.webview
+            .lock()
+            .unwrap()
+            .clear_browsing_data()
+            .map_err(|_| Error::FailedToSendMessage)?;
         Ok(())
     }
 
-    /// Unsupported, has no effect when called,
-    /// the versoview controls both the webview and the window
-    /// use the method from the parent window instead
+    /// Hides the window, since the webview fills the whole window
     fn hide(&self) -> Result<()> {
+        self.webview
+            .lock()
+            .unwrap()
+            .set_visible(false)
+            .map_err(|_| Error::FailedToSendMessage)?;
         Ok(())
     }
 
-    /// Unsupported, has no effect when called,
-    /// the versoview controls both the webview and the window
-    /// use the method from the parent window instead
+    /// Shows the window, since the webview fills the whole window
     fn show(&self) -> Result<()> {
+        self.webview
+            .lock()
+            .unwrap()
+            .set_visible(true)
+            .map_err(|_| Error::FailedToSendMessage)?;
         Ok(())
     }
 
@@ -178,18 +911,41 @@ impl<T: UserEvent> WebviewDispatch<T> for VersoWebviewDispatcher<T> {
         Ok(())
     }
 
-    /// Unsupported, has no effect when called
+    /// Starts the devtools server of this webview if it's not running,
+    /// on the port set by [`VersoRuntimeConfig::devtools_port`](crate::VersoRuntimeConfig::devtools_port) or a random one if it's not set,
+    /// and logs the port, connect to it from the `about:debugging` page in Firefox
+    ///
+    /// Use [`VersoWebviewDispatcher::devtools_port`] to get the port
     #[cfg(debug_assertions)]
-    fn open_devtools(&self) {}
+    fn open_devtools(&self) {
+        let webview = self.webview.lock().unwrap();
+        match webview.devtools_port() {
+            Ok(Some(port)) => log::info!("Devtools server is already running on port {port}"),
+            Ok(None) => {
+                match webview.start_devtools_server(self.context.verso_devtools_port().unwrap_or(0))
+                {
+                    Ok(port) => log::info!(
+                        "Devtools server started on port {port}, connect to it from the `about:debugging` page in Firefox"
+                    ),
+                    Err(error) => log::error!("Failed to start the devtools server: {error}"),
+                }
+            }
+            Err(error) => log::error!("Failed to start the devtools server: {error}"),
+        }
+    }
 
-    /// Unsupported, has no effect when called
+    /// Stops the devtools server of this webview
     #[cfg(debug_assertions)]
-    fn close_devtools(&self) {}
+    fn close_devtools(&self) {
+        if let Err(error) = self.webview.lock().unwrap().stop_devtools_server() {
+            log::error!("Failed to stop the devtools server: {error}");
+        }
+    }
 
-    /// Always false since we don't have devtools built-in
+    /// Returns if the devtools server of this webview is running
     #[cfg(debug_assertions)]
     fn is_devtools_open(&self) -> Result<bool> {
-        Ok(false)
+        Ok(self.devtools_port()?.is_some())
     }
 
     fn reload(&self) -> Result<()> {
@@ -211,3 +967,23 @@ impl<T: UserEvent> WebviewDispatch<T> for VersoWebviewDispatcher<T> {
         Ok(Vec::new())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emit_script_escapes_the_event_and_payload() {
+        let payload = serde_json::to_string("</script>\"quoted\"\nnext line").unwrap();
+        assert_eq!(
+            emit_script("it's \"done\"", &payload),
+            r#"window.dispatchEvent(new CustomEvent("it's \"done\"", { detail: JSON.parse("\"\u003c/script>\\\"quoted\\\"\\nnext line\"") }))"#
+        );
+    }
+
+    #[test]
+    fn emit_script_has_no_line_breaks() {
+        let payload = serde_json::to_string(&serde_json::json!({ "text": "a\nb\r\nc" })).unwrap();
+        assert!(!emit_script("event\nname", &payload).contains(['\n', '\r']));
+    }
+}
@@ -13,6 +13,7 @@ use tauri_runtime::{
     },
 };
 use tauri_utils::{Theme, config::WindowConfig};
+use url::Url;
 use verso::{VersoBuilder, VersoviewController};
 #[cfg(windows)]
 use windows::Win32::Foundation::HWND;
@@ -20,21 +21,259 @@ use windows::Win32::Foundation::HWND;
 use std::{
     collections::HashMap,
     fmt::{self, Debug},
-    sync::{Arc, Mutex},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicU32, Ordering},
+    },
+    time::{Duration, Instant},
 };
 
 use crate::{
-    RuntimeContext, VersoRuntime,
+    ProxyConfig, RuntimeContext, VersoRuntime,
     event_loop_ext::TaoEventLoopWindowTargetExt,
-    get_verso_devtools_port, get_verso_resource_directory,
     runtime::Message,
-    utils::{from_verso_theme, to_verso_theme},
+    utils::{from_verso_theme, to_verso_cursor_icon, to_verso_theme},
 };
 
+pub(crate) type SpawnVersoview = dyn Fn(Url) -> Result<VersoviewController> + Send + Sync;
+
 pub(crate) struct Window {
     pub(crate) label: String,
     pub(crate) webview: Arc<Mutex<VersoviewController>>,
     pub(crate) on_window_event_listeners: WindowEventListeners,
+    /// The last URL this window navigated to, used to restore the page on restart
+    pub(crate) current_url: Arc<Mutex<Url>>,
+    /// The process id of the versoview process, 0 if it's not running
+    pub(crate) process_id: Arc<AtomicU32>,
+    /// The zoom level set through [`WebviewDispatch::set_zoom`](tauri_runtime::WebviewDispatch::set_zoom)
+    pub(crate) zoom: Arc<Mutex<f64>>,
+    /// Spawns a new versoview process with the same settings this window was created with
+    pub(crate) spawn_versoview: Arc<SpawnVersoview>,
+    pub(crate) restart_backoff: RestartBackoff,
+    pub(crate) fullscreen: Arc<Mutex<FullscreenState>>,
+    /// See [`VersoWindowBuilder::close_requested_bridge`]
+    pub(crate) close_requested_bridge: bool,
+    /// Waiting for the page to allow or cancel closing the window
+    pub(crate) close_request_pending: Arc<AtomicBool>,
+    /// If the page wanted a `beforeunload` prompt on the last close request,
+    /// see [`VersoWindowDispatcher::close_prevented_by_unload_prompt`]
+    pub(crate) unload_prompt: Arc<AtomicBool>,
+    /// The theme set for this window through the builder or [`WindowDispatch::set_theme`],
+    /// the window follows the app or system theme if this is `None`
+    pub(crate) explicit_theme: Arc<Mutex<Option<Theme>>>,
+}
+
+/// The fullscreen state of a window and the geometry changes requested while it's fullscreen,
+/// which are applied after leaving fullscreen through [`WindowDispatch::set_fullscreen`]
+/// or through the OS (e.g. pressing Esc or the green button on macOS)
+#[derive(Debug, Default)]
+pub(crate) struct FullscreenState {
+    pub(crate) fullscreen: bool,
+    pub(crate) pending_size: Option<Size>,
+    pub(crate) pending_position: Option<Position>,
+    /// The monitor from [`VersoWindowDispatcher::set_fullscreen_on_monitor`]
+    pub(crate) monitor: Option<Monitor>,
+}
+
+/// The window operations [`FullscreenState`] uses, implemented for [`VersoviewController`]
+pub(crate) trait FullscreenWindow {
+    fn is_fullscreen(&self) -> Result<bool>;
+    fn set_fullscreen(&self, fullscreen: bool) -> Result<()>;
+    fn set_size(&self, size: Size) -> Result<()>;
+    fn set_position(&self, position: Position) -> Result<()>;
+}
+
+impl FullscreenWindow for VersoviewController {
+    fn is_fullscreen(&self) -> Result<bool> {
+        VersoviewController::is_fullscreen(self).map_err(|_| Error::FailedToSendMessage)
+    }
+
+    fn set_fullscreen(&self, fullscreen: bool) -> Result<()> {
+        VersoviewController::set_fullscreen(self, fullscreen)
+            .map_err(|_| Error::FailedToSendMessage)
+    }
+
+    fn set_size(&self, size: Size) -> Result<()> {
+        VersoviewController::set_size(self, size).map_err(|_| Error::FailedToSendMessage)
+    }
+
+    fn set_position(&self, position: Position) -> Result<()> {
+        VersoviewController::set_position(self, position).map_err(|_| Error::FailedToSendMessage)
+    }
+}
+
+impl FullscreenState {
+    /// Leaves fullscreen and applies the size and position requested while fullscreen
+    pub(crate) fn leave(&mut self, webview: &impl FullscreenWindow) -> Result<()> {
+        webview.set_fullscreen(false)?;
+        self.left(webview)
+    }
+
+    /// Clears the fullscreen state and applies the size and position requested while fullscreen
+    fn left(&mut self, webview: &impl FullscreenWindow) -> Result<()> {
+        self.fullscreen = false;
+        self.monitor = None;
+        if let Some(size) = self.pending_size.take() {
+            webview.set_size(size)?;
+        }
+        if let Some(position) = self.pending_position.take() {
+            webview.set_position(position)?;
+        }
+        Ok(())
+    }
+
+    /// Picks up entering or leaving fullscreen through the OS, called when the window is resized,
+    /// which versoview notifies us of on both
+    pub(crate) fn resized<W: FullscreenWindow>(
+        state: &Mutex<Self>,
+        webview: &Mutex<W>,
+    ) -> Result<()> {
+        let mut state = state.lock().unwrap();
+        let webview = webview.lock().unwrap();
+        let fullscreen = webview.is_fullscreen()?;
+        if state.fullscreen && !fullscreen {
+            return state.left(&*webview);
+        }
+        state.fullscreen = fullscreen;
+        Ok(())
+    }
+
+    /// Sets the inner size of the window, or keeps it to apply after leaving fullscreen if the window is fullscreen
+    pub(crate) fn set_size<W: FullscreenWindow>(
+        state: &Mutex<Self>,
+        webview: &Mutex<W>,
+        size: Size,
+    ) -> Result<()> {
+        let mut state = state.lock().unwrap();
+        let webview = webview.lock().unwrap();
+        if state.fullscreen {
+            state.pending_size = Some(size);
+            return Ok(());
+        }
+        webview.set_size(size)
+    }
+
+    /// Sets the outer position of the window, or keeps it to apply after leaving fullscreen if the window is fullscreen
+    pub(crate) fn set_position<W: FullscreenWindow>(
+        state: &Mutex<Self>,
+        webview: &Mutex<W>,
+        position: Position,
+    ) -> Result<()> {
+        let mut state = state.lock().unwrap();
+        let webview = webview.lock().unwrap();
+        if state.fullscreen {
+            state.pending_position = Some(position);
+            return Ok(());
+        }
+        webview.set_position(position)
+    }
+}
+
+/// Exponential backoff for restarting crashed versoview processes,
+/// so a page that reliably crashes versoview doesn't make us spin
+#[derive(Debug, Default)]
+pub(crate) struct RestartBackoff {
+    attempts: u32,
+    last_restart: Option<Instant>,
+}
+
+impl RestartBackoff {
+    const MAX_ATTEMPTS: u32 = 5;
+    const BASE_DELAY: Duration = Duration::from_millis(500);
+    /// Consider the process stable again if it survived for this long after the last restart
+    const RESET_AFTER: Duration = Duration::from_secs(60);
+
+    /// Returns the delay to wait before the next restart,
+    /// or [`None`] if we already restarted too many times in a short period
+    pub(crate) fn next_delay(&mut self) -> Option<Duration> {
+        if self
+            .last_restart
+            .is_some_and(|last_restart| last_restart.elapsed() > Self::RESET_AFTER)
+        {
+            self.attempts = 0;
+        }
+        if self.attempts >= Self::MAX_ATTEMPTS {
+            return None;
+        }
+        let delay = Self::BASE_DELAY * 2u32.pow(self.attempts);
+        self.attempts += 1;
+        self.last_restart = Some(Instant::now());
+        Some(delay)
+    }
+}
+
+/// What to do with a request from the page to open a new window,
+/// returned from the handler set by [`VersoWindowBuilder::on_new_window`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NewWindowResponse {
+    /// Don't open anything, e.g. because the handler already opened the URL
+    /// in a new window or in the system browser
+    Deny,
+    /// Load the URL in the window that made the request instead
+    Navigate,
+}
+
+#[derive(Clone)]
+pub(crate) struct NewWindowHandler(pub(crate) Arc<dyn Fn(Url) -> NewWindowResponse + Send + Sync>);
+
+impl Debug for NewWindowHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NewWindowHandler").finish_non_exhaustive()
+    }
+}
+
+/// How a navigation ended, passed to the handler set by [`VersoWindowBuilder::on_navigation_finished`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NavigationOutcome {
+    /// The page loaded with this HTTP status code,
+    /// note error pages (e.g. a 404 page from the server) are also loaded successfully
+    Succeeded { status: u16 },
+    /// The page failed to load because of a network error, e.g. the server can't be reached
+    Failed { error: String },
+    /// The navigation was stopped before it finished, e.g. by another navigation or `window.stop()`
+    Aborted,
+}
+
+#[derive(Clone)]
+pub(crate) struct NavigationFinishedHandler(
+    pub(crate) Arc<dyn Fn(Url, NavigationOutcome) + Send + Sync>,
+);
+
+impl Debug for NavigationFinishedHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NavigationFinishedHandler")
+            .finish_non_exhaustive()
+    }
+}
+
+/// A page load event, passed to the handler set by [`VersoWindowBuilder::on_load_event`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadEvent {
+    /// A page started loading
+    Started { url: Url },
+    /// A page finished loading
+    Finished { url: Url },
+    /// A page failed to load because of a network error, e.g. a DNS or TLS error
+    Failed { url: Url, error: String },
+}
+
+#[derive(Clone)]
+pub(crate) struct LoadEventHandler(pub(crate) Arc<dyn Fn(LoadEvent) + Send + Sync>);
+
+impl Debug for LoadEventHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoadEventHandler").finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct MixedContentBlockedHandler(pub(crate) Arc<dyn Fn(Url, Url) + Send + Sync>);
+
+impl Debug for MixedContentBlockedHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MixedContentBlockedHandler")
+            .finish_non_exhaustive()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -42,29 +281,278 @@ pub struct VersoWindowBuilder {
     pub verso_builder: VersoBuilder,
     pub has_icon: bool,
     pub theme: Option<Theme>,
+    /// See [`VersoWindowBuilder::capture_output`], falls back to [`VersoRuntimeConfig::capture_output`](crate::VersoRuntimeConfig::capture_output)
+    pub(crate) capture_output: Option<bool>,
+    /// The parent or owner window set on this builder is a null handle,
+    /// creating the window will fail with [`tauri_runtime::Error::CreateWindow`]
+    pub has_invalid_parent: bool,
+    /// Tracked separately from `always_on_bottom` and resolved to a [`verso::WindowLevel`] on creation,
+    /// `always_on_top` takes precedence if both are set
+    pub always_on_top: bool,
+    pub always_on_bottom: bool,
+    pub(crate) new_window_handler: Option<NewWindowHandler>,
+    pub(crate) navigation_finished_handler: Option<NavigationFinishedHandler>,
+    pub(crate) mixed_content_blocked_handler: Option<MixedContentBlockedHandler>,
+    pub(crate) load_event_handler: Option<LoadEventHandler>,
+    pub(crate) close_requested_bridge: bool,
+    /// Only used on Windows, versoview does it on other platforms
+    #[cfg(windows)]
+    pub(crate) kill_on_parent_exit: bool,
+    pub(crate) fullscreen: bool,
+    /// Stylesheets to inject into every page, see [`VersoWindowBuilder::inject_css`]
+    pub(crate) css: Vec<String>,
 }
 
 impl Default for VersoWindowBuilder {
     fn default() -> Self {
         let mut verso_builder = VersoBuilder::new();
-        if let Some(resource_directory) = get_verso_resource_directory() {
-            verso_builder = verso_builder.resources_directory(resource_directory);
-        }
-        if let Some(devtools_port) = get_verso_devtools_port() {
-            verso_builder = verso_builder.devtools_port(devtools_port);
-        }
         // Default `decorated` to `true` to align with the wry runtime
         verso_builder = verso_builder.decorated(true);
         // Default `transparent` to `false` to align with the wry runtime
         verso_builder = verso_builder.transparent(false);
+        // Block mixed content by default like the browsers do
+        verso_builder = verso_builder.block_mixed_content(true);
+        verso_builder = verso_builder.pause_rendering_when_hidden(true);
+        verso_builder = verso_builder.kill_on_parent_exit(true);
+        // Default `zoom_hotkeys_enabled` to `false` to align with the wry runtime
+        verso_builder = verso_builder.zoom_hotkeys_enabled(false);
+        // Default `drag_and_drop` to `true` to align with the wry runtime
+        #[cfg(windows)]
+        {
+            verso_builder = verso_builder.drag_and_drop(true);
+        }
         Self {
             verso_builder,
             has_icon: false,
             theme: None,
+            capture_output: None,
+            has_invalid_parent: false,
+            always_on_top: false,
+            always_on_bottom: false,
+            new_window_handler: None,
+            navigation_finished_handler: None,
+            mixed_content_blocked_handler: None,
+            load_event_handler: None,
+            close_requested_bridge: false,
+            #[cfg(windows)]
+            kill_on_parent_exit: true,
+            fullscreen: false,
+            css: Vec::new(),
         }
     }
 }
 
+impl VersoWindowBuilder {
+    /// The window level resolved from `always_on_top` and `always_on_bottom`
+    pub fn window_level(&self) -> verso::WindowLevel {
+        if self.always_on_top {
+            verso::WindowLevel::AlwaysOnTop
+        } else if self.always_on_bottom {
+            verso::WindowLevel::AlwaysOnBottom
+        } else {
+            verso::WindowLevel::Normal
+        }
+    }
+
+    /// Sets the `User-Agent` of the webview,
+    /// this can only be set on creation and can't be changed afterwards
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.verso_builder = self.verso_builder.user_agent(user_agent.into());
+        self
+    }
+
+    /// Sets whether the user can change the zoom level of the page with
+    /// <kbd>Ctrl</kbd>/<kbd>Cmd</kbd> + <kbd>=</kbd>, <kbd>-</kbd> and <kbd>0</kbd>,
+    /// defaults to `false`, this can only be set on creation
+    pub fn zoom_hotkeys_enabled(mut self, enabled: bool) -> Self {
+        self.verso_builder = self.verso_builder.zoom_hotkeys_enabled(enabled);
+        self
+    }
+
+    /// Injects a stylesheet into every page loaded in this window, at the start of the document,
+    /// use [`VersoWebviewDispatcher::inject_css`](crate::VersoWebviewDispatcher::inject_css) for the current page only
+    pub fn inject_css(mut self, css: impl Into<String>) -> Self {
+        self.css.push(css.into());
+        self
+    }
+
+    /// Sets a handler for the page requesting a new window,
+    /// e.g. through `window.open` or clicking a link with `target="_blank"`,
+    /// the handler receives the target URL and decides what to do with it,
+    /// it can open a new window or open the URL in the system browser itself and return [`NewWindowResponse::Deny`],
+    /// or return [`NewWindowResponse::Navigate`] to load the URL in this window
+    ///
+    /// The handler is called on a background thread,
+    /// without a handler, the request is denied and a warning is logged
+    pub fn on_new_window(
+        mut self,
+        handler: impl Fn(Url) -> NewWindowResponse + Send + Sync + 'static,
+    ) -> Self {
+        self.new_window_handler = Some(NewWindowHandler(Arc::new(handler)));
+        self
+    }
+
+    /// Sets a handler to be called with the final URL (after redirects) and the outcome when a navigation ends,
+    /// to tell apart a page loaded with an HTTP status, a network error and an aborted navigation,
+    /// use the `navigation_handler` of the webview for when a navigation starts
+    ///
+    /// The handler is called on a background thread
+    pub fn on_navigation_finished(
+        mut self,
+        handler: impl Fn(Url, NavigationOutcome) + Send + Sync + 'static,
+    ) -> Self {
+        self.navigation_finished_handler = Some(NavigationFinishedHandler(Arc::new(handler)));
+        self
+    }
+
+    /// Sets a handler to be called when a page starts loading, finishes loading or fails to load,
+    /// e.g. to show an offline fallback page when a page fails to load
+    ///
+    /// The handler is called on a background thread
+    pub fn on_load_event(mut self, handler: impl Fn(LoadEvent) + Send + Sync + 'static) -> Self {
+        self.load_event_handler = Some(LoadEventHandler(Arc::new(handler)));
+        self
+    }
+
+    /// Sets whether to let the page delay or cancel closing the window, defaults to `false`
+    ///
+    /// When enabled, a close request that's not prevented through [`WindowEvent::CloseRequested`]
+    /// dispatches a `tauri-verso://close-requested` event on `window` in the page,
+    /// the page can cancel the close with `event.preventDefault()`,
+    /// or delay it with `event.waitUntil(promise)` to do some async cleanup first,
+    /// the close is cancelled if the promise resolves to `false` or rejects,
+    /// and the window is closed anyway if the page doesn't settle it in 10 seconds
+    ///
+    /// ```js
+    /// window.addEventListener('tauri-verso://close-requested', (event) => {
+    ///   event.waitUntil(saveDraft())
+    /// })
+    /// ```
+    pub fn close_requested_bridge(mut self, enabled: bool) -> Self {
+        self.close_requested_bridge = enabled;
+        self
+    }
+
+    /// Sets whether to run versoview headless, without showing the window or rendering on screen,
+    /// the page still loads and runs, so the IPC, [`WebviewDispatch::eval_script`](tauri_runtime::WebviewDispatch::eval_script)
+    /// and [`VersoWebviewDispatcher::capture_page`](crate::VersoWebviewDispatcher::capture_page) keep working,
+    /// useful for running tests without a display server, defaults to `false`
+    ///
+    /// See [`VersoRuntimeConfig::headless`](crate::VersoRuntimeConfig::headless) to make every window headless
+    pub fn headless(mut self, headless: bool) -> Self {
+        self.verso_builder = self.verso_builder.headless(headless);
+        self
+    }
+
+    /// Sets whether to capture the stdout and stderr of the versoview process
+    /// and forward them to the [`log`] crate with the window label as the target,
+    /// set it to `false` to let the versoview process print to the console directly
+    ///
+    /// Defaults to [`VersoRuntimeConfig::capture_output`](crate::VersoRuntimeConfig::capture_output)
+    pub fn capture_output(mut self, capture: bool) -> Self {
+        self.capture_output = Some(capture);
+        self
+    }
+
+    /// Sets whether to kill the versoview process when this process exits,
+    /// even if it crashes or gets killed, defaults to `true` so there're no windows left behind,
+    /// turn this off if you intentionally leave the window running after the app exits
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Windows**: the versoview process is put into a job object that's closed when this process exits
+    /// - **Linux / macOS**: versoview watches this process and exits with it
+    pub fn kill_on_parent_exit(mut self, enabled: bool) -> Self {
+        #[cfg(windows)]
+        {
+            self.kill_on_parent_exit = enabled;
+        }
+        self.verso_builder = self.verso_builder.kill_on_parent_exit(enabled);
+        self
+    }
+
+    /// Sets whether versoview stops rendering the page while the window is hidden (through `hide`) or minimized,
+    /// and resumes when it's shown again, defaults to `true` to cut the idle resource usage (e.g. for tray apps)
+    ///
+    /// Turn this off if the page needs to keep rendering in the background, e.g. for capturing it with
+    /// [`VersoWebviewDispatcher::capture_page`](crate::VersoWebviewDispatcher::capture_page),
+    /// note timers and `requestAnimationFrame` callbacks are throttled while paused
+    pub fn pause_rendering_when_hidden(mut self, enabled: bool) -> Self {
+        self.verso_builder = self.verso_builder.pause_rendering_when_hidden(enabled);
+        self
+    }
+
+    /// Sets whether to run JavaScript in the pages, defaults to `true`,
+    /// use [`VersoWebviewDispatcher::set_javascript_enabled`](crate::VersoWebviewDispatcher::set_javascript_enabled)
+    /// to change it after creation
+    ///
+    /// Note the Tauri APIs (commands, events and plugins) don't work in the page when this is disabled,
+    /// since the IPC is done through JavaScript, this is for rendering untrusted HTML (e.g. a document preview)
+    pub fn javascript_enabled(mut self, enabled: bool) -> Self {
+        if !enabled {
+            log::warn!("JavaScript is disabled, the Tauri APIs won't work in this window");
+        }
+        self.verso_builder = self.verso_builder.javascript_enabled(enabled);
+        self
+    }
+
+    /// Sets whether to block loading `http` resources (e.g. images and scripts) on `https` pages,
+    /// defaults to `true`, use [`VersoWebviewDispatcher::set_block_mixed_content`](crate::VersoWebviewDispatcher::set_block_mixed_content)
+    /// to change it after creation
+    pub fn block_mixed_content(mut self, block: bool) -> Self {
+        self.verso_builder = self.verso_builder.block_mixed_content(block);
+        self
+    }
+
+    /// Sets a handler to be called with the page URL and the resource URL
+    /// when a mixed content resource is blocked, see [`VersoWindowBuilder::block_mixed_content`]
+    ///
+    /// The handler is called on a background thread
+    pub fn on_mixed_content_blocked(
+        mut self,
+        handler: impl Fn(Url, Url) + Send + Sync + 'static,
+    ) -> Self {
+        self.mixed_content_blocked_handler = Some(MixedContentBlockedHandler(Arc::new(handler)));
+        self
+    }
+
+    /// Sets `WM_TRANSIENT_FOR` of the created window to the X11 window `parent`,
+    /// so the window manager keeps it above its parent like a dialog
+    ///
+    /// Creating the window fails with [`tauri_runtime::Error::CreateWindow`] if `parent` is 0
+    ///
+    /// ## Platform-specific
+    ///
+    /// **Wayland**: Unsupported, has no effect, there're no global window ids on Wayland,
+    /// the window will be created as a normal top level window
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    pub fn transient_for_x11(mut self, parent: u64) -> Self {
+        self.has_invalid_parent |= parent == 0;
+        self.verso_builder = self.verso_builder.parent_window(parent as isize);
+        self
+    }
+
+    /// Sets the preferred languages of the webview in order, e.g. `["en-US", "en"]`,
+    /// this is used for the `Accept-Language` header of the requests
+    /// and `navigator.languages` instead of the system locale
+    pub fn accept_languages(mut self, languages: Vec<String>) -> Self {
+        self.verso_builder = self.verso_builder.accept_languages(languages);
+        self
+    }
+
+    /// Routes the network requests of the webview through an HTTP or SOCKS5 proxy
+    pub fn proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.verso_builder = self.verso_builder.proxy(proxy.to_url());
+        self
+    }
+}
+
 impl WindowBuilderBase for VersoWindowBuilder {}
 
 impl WindowBuilder for VersoWindowBuilder {
@@ -73,31 +561,72 @@ impl WindowBuilder for VersoWindowBuilder {
     }
 
     fn with_config(config: &WindowConfig) -> Self {
-        let builder = Self::default();
-        let mut verso_builder = builder.verso_builder;
-        verso_builder = verso_builder
+        // Go through the builder methods so the fields are handled
+        // the same way as setting them on the builder directly
+        let mut builder = Self::default()
+            .title(config.title.clone())
+            .inner_size(config.width, config.height)
             .focused(config.focus)
             .fullscreen(config.fullscreen)
             .maximized(config.maximized)
             .visible(config.visible)
-            .inner_size(LogicalSize::new(config.width, config.height))
-            .title(config.title.clone())
-            .decorated(config.decorations)
-            .transparent(config.transparent);
+            .decorations(config.decorations)
+            .resizable(config.resizable)
+            .maximizable(config.maximizable)
+            .minimizable(config.minimizable)
+            .closable(config.closable)
+            .always_on_bottom(config.always_on_bottom)
+            .always_on_top(config.always_on_top)
+            .visible_on_all_workspaces(config.visible_on_all_workspaces)
+            .content_protected(config.content_protected)
+            .skip_taskbar(config.skip_taskbar)
+            .shadow(config.shadow)
+            .theme(config.theme);
+
+        // `transparent` is not available on macOS without the `macos-private-api` feature
+        builder.verso_builder = builder.verso_builder.transparent(config.transparent);
 
         if let (Some(x), Some(y)) = (config.x, config.y) {
-            verso_builder = verso_builder.position(LogicalPosition::new(x, y));
-        };
+            builder = builder.position(x, y);
+        }
 
-        if let Some(theme) = config.theme {
-            verso_builder = verso_builder.theme(to_verso_theme(theme));
+        if config.center {
+            builder = builder.center();
         }
 
-        Self {
-            verso_builder,
-            has_icon: false,
-            theme: None,
+        if let (Some(min_width), Some(min_height)) = (config.min_width, config.min_height) {
+            builder = builder.min_inner_size(min_width, min_height);
+        }
+
+        if let (Some(max_width), Some(max_height)) = (config.max_width, config.max_height) {
+            builder = builder.max_inner_size(max_width, max_height);
+        }
+
+        if let Some(window_classname) = &config.window_classname {
+            builder = builder.window_classname(window_classname);
+        }
+
+        #[cfg(windows)]
+        {
+            builder = builder.drag_and_drop(config.drag_drop_enabled);
         }
+
+        #[cfg(target_os = "macos")]
+        if let Some(tabbing_identifier) = &config.tabbing_identifier {
+            builder = builder.tabbing_identifier(tabbing_identifier);
+        }
+
+        if let Some(proxy) = config.proxy_url.as_ref().and_then(ProxyConfig::from_url) {
+            builder = builder.proxy(proxy);
+        }
+
+        if let Some(user_agent) = &config.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+
+        builder = builder.zoom_hotkeys_enabled(config.zoom_hotkeys_enabled);
+
+        builder
     }
 
     /// Unsupported, has no effect
@@ -119,13 +648,19 @@ impl WindowBuilder for VersoWindowBuilder {
         self
     }
 
-    /// Unsupported, has no effect
-    fn min_inner_size(self, min_width: f64, min_height: f64) -> Self {
+    /// Note: min_width and min_height are in logical unit
+    fn min_inner_size(mut self, min_width: f64, min_height: f64) -> Self {
+        self.verso_builder = self
+            .verso_builder
+            .min_inner_size(LogicalSize::new(min_width, min_height));
         self
     }
 
-    /// Unsupported, has no effect
-    fn max_inner_size(self, max_width: f64, max_height: f64) -> Self {
+    /// Note: max_width and max_height are in logical unit
+    fn max_inner_size(mut self, max_width: f64, max_height: f64) -> Self {
+        self.verso_builder = self
+            .verso_builder
+            .max_inner_size(LogicalSize::new(max_width, max_height));
         self
     }
 
@@ -137,23 +672,23 @@ impl WindowBuilder for VersoWindowBuilder {
         self
     }
 
-    /// Unsupported, has no effect
-    fn resizable(self, resizable: bool) -> Self {
+    fn resizable(mut self, resizable: bool) -> Self {
+        self.verso_builder = self.verso_builder.resizable(resizable);
         self
     }
 
-    /// Unsupported, has no effect
-    fn maximizable(self, resizable: bool) -> Self {
+    fn maximizable(mut self, maximizable: bool) -> Self {
+        self.verso_builder = self.verso_builder.maximizable(maximizable);
         self
     }
 
-    /// Unsupported, has no effect
-    fn minimizable(self, resizable: bool) -> Self {
+    fn minimizable(mut self, minimizable: bool) -> Self {
+        self.verso_builder = self.verso_builder.minimizable(minimizable);
         self
     }
 
-    /// Unsupported, has no effect
-    fn closable(self, resizable: bool) -> Self {
+    fn closable(mut self, closable: bool) -> Self {
+        self.verso_builder = self.verso_builder.closable(closable);
         self
     }
 
@@ -163,6 +698,7 @@ impl WindowBuilder for VersoWindowBuilder {
     }
 
     fn fullscreen(mut self, fullscreen: bool) -> Self {
+        self.fullscreen = fullscreen;
         self.verso_builder = self.verso_builder.fullscreen(fullscreen);
         self
     }
@@ -188,20 +724,12 @@ impl WindowBuilder for VersoWindowBuilder {
     }
 
     fn always_on_bottom(mut self, always_on_bottom: bool) -> Self {
-        self.verso_builder = self.verso_builder.window_level(if always_on_bottom {
-            verso::WindowLevel::AlwaysOnTop
-        } else {
-            verso::WindowLevel::Normal
-        });
+        self.always_on_bottom = always_on_bottom;
         self
     }
 
     fn always_on_top(mut self, always_on_top: bool) -> Self {
-        self.verso_builder = self.verso_builder.window_level(if always_on_top {
-            verso::WindowLevel::AlwaysOnTop
-        } else {
-            verso::WindowLevel::Normal
-        });
+        self.always_on_top = always_on_top;
         self
     }
 
@@ -230,8 +758,18 @@ impl WindowBuilder for VersoWindowBuilder {
         self
     }
 
-    /// Unsupported, has no effect
-    fn window_classname<S: Into<String>>(self, classname: S) -> Self {
+    /// Sets the window class name (Windows) or `WM_CLASS` (X11) of the native window,
+    /// this can only be set on creation
+    ///
+    /// ## Platform-specific
+    ///
+    /// **macOS**: Unsupported, has no effect
+    #[cfg_attr(target_os = "macos", allow(unused_mut))]
+    fn window_classname<S: Into<String>>(mut self, classname: S) -> Self {
+        #[cfg(not(target_os = "macos"))]
+        {
+            self.verso_builder = self.verso_builder.window_classname(classname);
+        }
         self
     }
 
@@ -240,13 +778,37 @@ impl WindowBuilder for VersoWindowBuilder {
         self
     }
 
-    /// Unsupported, has no effect
+    /// Sets the parent window, `parent` is a pointer to an `NSWindow`,
+    /// the created window will become a child window of it
+    ///
+    /// Since the window lives in the versoview process,
+    /// we pass the window number of `parent` to versoview instead of the pointer,
+    /// it's read on the main thread, so this blocks until the main thread gets to it when called from another thread
+    ///
+    /// Creating the window fails with [`tauri_runtime::Error::CreateWindow`] if `parent` is null
     #[cfg(target_os = "macos")]
-    fn parent(self, parent: *mut std::ffi::c_void) -> Self {
+    fn parent(mut self, parent: *mut std::ffi::c_void) -> Self {
+        if parent.is_null() {
+            self.has_invalid_parent = true;
+            return self;
+        }
+        // Raw pointers are not `Send`, pass the address to the main thread instead
+        let parent = parent as usize;
+        let window_number = dispatch2::run_on_main(move |_| {
+            // SAFETY: `parent` is a non-null pointer to an `NSWindow` given by the caller (e.g. from `ns_window`),
+            // which is kept alive by its window while building this one,
+            // and `NSWindow` (including `windowNumber`) is only used here on the main thread
+            unsafe {
+                let ns_window = &*(parent as *const objc2_app_kit::NSWindow);
+                ns_window.windowNumber()
+            }
+        });
+        self.verso_builder = self.verso_builder.parent_window(window_number as isize);
         self
     }
 
-    /// Unsupported, has no effect
+    /// Unsupported, has no effect, a GTK window can't be shared with the versoview process,
+    /// use [`VersoWindowBuilder::transient_for_x11`] with the X11 window id instead
     #[cfg(any(
         target_os = "linux",
         target_os = "dragonfly",
@@ -258,9 +820,12 @@ impl WindowBuilder for VersoWindowBuilder {
         self
     }
 
-    /// Unsupported, has no effect
+    /// Enables or disables the drop target of the webview,
+    /// disable it if you want to register your own drop target on the window,
+    /// this can only be set on creation
     #[cfg(windows)]
-    fn drag_and_drop(self, enabled: bool) -> Self {
+    fn drag_and_drop(mut self, enabled: bool) -> Self {
+        self.verso_builder = self.verso_builder.drag_and_drop(enabled);
         self
     }
 
@@ -276,9 +841,11 @@ impl WindowBuilder for VersoWindowBuilder {
         self
     }
 
-    /// Unsupported, has no effect
+    /// Windows with the same tabbing identifier will be grouped together as native tabs,
+    /// use [`VersoWindowDispatcher::is_tabbed`] to check if the window ended up in a tab group
     #[cfg(target_os = "macos")]
-    fn tabbing_identifier(self, identifier: &str) -> Self {
+    fn tabbing_identifier(mut self, identifier: &str) -> Self {
+        self.verso_builder = self.verso_builder.tabbing_identifier(identifier);
         self
     }
 
@@ -291,8 +858,8 @@ impl WindowBuilder for VersoWindowBuilder {
     fn theme(mut self, theme: Option<Theme>) -> Self {
         if let Some(theme) = theme {
             self.verso_builder = self.verso_builder.theme(to_verso_theme(theme));
-            self.theme = Some(theme);
         }
+        self.theme = theme;
         self
     }
 
@@ -309,15 +876,24 @@ impl WindowBuilder for VersoWindowBuilder {
         self
     }
 
-    /// Unsupported, has no effect
+    /// Sets the owner window, the created window will always stay above its owner,
+    /// and will be minimized and destroyed together with its owner
+    ///
+    /// Creating the window fails with [`tauri_runtime::Error::CreateWindow`] if `owner` is null
     #[cfg(windows)]
-    fn owner(self, owner: HWND) -> Self {
+    fn owner(mut self, owner: HWND) -> Self {
+        self.has_invalid_parent |= owner.is_invalid();
+        self.verso_builder = self.verso_builder.owner_window(owner.0 as isize);
         self
     }
 
-    /// Unsupported, has no effect
+    /// Sets the parent window, the created window will become a child window of `parent`
+    ///
+    /// Creating the window fails with [`tauri_runtime::Error::CreateWindow`] if `parent` is null
     #[cfg(windows)]
-    fn parent(self, parent: HWND) -> Self {
+    fn parent(mut self, parent: HWND) -> Self {
+        self.has_invalid_parent |= parent.is_invalid();
+        self.verso_builder = self.verso_builder.parent_window(parent.0 as isize);
         self
     }
 
@@ -352,6 +928,102 @@ pub struct VersoWindowDispatcher<T: UserEvent> {
     pub(crate) context: RuntimeContext<T>,
     pub(crate) webview: Arc<Mutex<VersoviewController>>,
     pub(crate) on_window_event_listeners: WindowEventListeners,
+    pub(crate) process_id: Arc<AtomicU32>,
+    pub(crate) fullscreen: Arc<Mutex<FullscreenState>>,
+    /// The focus state from the last [`WindowEvent::Focused`](tauri_runtime::window::WindowEvent::Focused)
+    pub(crate) focused: Arc<AtomicBool>,
+    pub(crate) explicit_theme: Arc<Mutex<Option<Theme>>>,
+    pub(crate) unload_prompt: Arc<AtomicBool>,
+}
+
+impl<T: UserEvent> VersoWindowDispatcher<T> {
+    /// Makes the window fullscreen on `monitor` (from [`WindowDispatch::available_monitors`]),
+    /// e.g. for showing a presentation on a projector,
+    /// the window is moved back to where it was after leaving fullscreen through [`WindowDispatch::set_fullscreen`]
+    ///
+    /// If the monitor is disconnected while the window is fullscreen on it,
+    /// the window leaves fullscreen and is moved onto a remaining monitor
+    pub fn set_fullscreen_on_monitor(&self, monitor: Monitor) -> Result<()> {
+        let mut state = self.fullscreen.lock().unwrap();
+        let webview = self.webview.lock().unwrap();
+        if state.fullscreen {
+            webview
+                .set_fullscreen(false)
+                .map_err(|_| Error::FailedToSendMessage)?;
+        } else if state.pending_position.is_none() {
+            if let Ok(Some(position)) = webview.get_outer_position() {
+                state.pending_position = Some(position.into());
+            }
+        }
+        // Versoview goes fullscreen on the monitor the window is on, so move the window there first
+        webview
+            .set_position(monitor.position.into())
+            .map_err(|_| Error::FailedToSendMessage)?;
+        webview
+            .set_fullscreen(true)
+            .map_err(|_| Error::FailedToSendMessage)?;
+        state.fullscreen = true;
+        state.monitor = Some(monitor);
+        Ok(())
+    }
+
+    /// Returns the monitor the center of the window is on,
+    /// or [`None`] if it's not on any monitor (e.g. the window is off screen)
+    ///
+    /// ## Platform-specific
+    ///
+    /// **Wayland**: the window position is not available, so this always returns the monitor at the top left
+    pub fn monitor_from_window(&self) -> Result<Option<Monitor>> {
+        let position = self.outer_position()?;
+        let size = self.outer_size()?;
+        let x = position.x as f64 + size.width as f64 / 2.0;
+        let y = position.y as f64 + size.height as f64 / 2.0;
+        self.monitor_from_point(x, y)
+    }
+
+    /// Returns the OS process id of the versoview process of this window,
+    /// or [`None`] if the process has exited
+    ///
+    /// This doesn't communicate with versoview, so it never blocks
+    pub fn process_id(&self) -> Option<u32> {
+        match self.process_id.load(Ordering::Relaxed) {
+            0 => None,
+            process_id => Some(process_id),
+        }
+    }
+
+    /// Returns if the page has a `beforeunload` handler that wants to prompt the user before leaving,
+    /// e.g. because there're unsaved changes
+    ///
+    /// This waits on versoview, use [`VersoWindowDispatcher::close_prevented_by_unload_prompt`]
+    /// in the [`WindowEvent::CloseRequested`] handler instead
+    pub fn has_unload_prompt(&self) -> Result<bool> {
+        self.webview
+            .lock()
+            .unwrap()
+            .has_unload_prompt()
+            .map_err(|_| Error::FailedToSendMessage)
+    }
+
+    /// Returns if the last close request is prevented because the page has a `beforeunload` handler
+    /// that wants to prompt the user before leaving, e.g. because there're unsaved changes
+    ///
+    /// This is set before [`WindowEvent::CloseRequested`] is emitted and doesn't communicate with versoview,
+    /// so you can check it in the event handler, show a confirmation and call [`WindowDispatch::destroy`] to close it anyway
+    pub fn close_prevented_by_unload_prompt(&self) -> bool {
+        self.unload_prompt.load(Ordering::Relaxed)
+    }
+
+    /// Returns if the window is in a native tab group with other windows,
+    /// the geometry getters like [`WindowDispatch::outer_size`] return the geometry of the tab group's window in this case
+    #[cfg(target_os = "macos")]
+    pub fn is_tabbed(&self) -> Result<bool> {
+        self.webview
+            .lock()
+            .unwrap()
+            .is_tabbed()
+            .map_err(|_| Error::FailedToSendMessage)
+    }
 }
 
 impl<T: UserEvent> Debug for VersoWindowDispatcher<T> {
@@ -373,7 +1045,14 @@ impl<T: UserEvent> WindowDispatch<T> for VersoWindowDispatcher<T> {
         self.context.run_on_main_thread(f)
     }
 
-    /// Currently only [`WindowEvent::CloseRequested`] will be emitted
+    /// Currently only these events will be emitted:
+    /// - [`WindowEvent::CloseRequested`]
+    /// - [`WindowEvent::ThemeChanged`]
+    /// - [`WindowEvent::DragDrop`]
+    /// - [`WindowEvent::ScaleFactorChanged`]
+    /// - [`WindowEvent::Focused`]
+    /// - [`WindowEvent::Moved`]
+    /// - [`WindowEvent::Resized`]
     fn on_window_event<F: Fn(&WindowEvent) + Send + 'static>(&self, f: F) -> WindowEventId {
         let id = self.context.next_window_event_id();
         self.on_window_event_listeners
@@ -461,9 +1140,8 @@ impl<T: UserEvent> WindowDispatch<T> for VersoWindowDispatcher<T> {
             .map_err(|_| Error::FailedToSendMessage)
     }
 
-    /// Unsupported, always returns false
     fn is_focused(&self) -> Result<bool> {
-        Ok(false)
+        Ok(self.focused.load(Ordering::Relaxed))
     }
 
     /// Unsupported, always returns false
@@ -471,24 +1149,36 @@ impl<T: UserEvent> WindowDispatch<T> for VersoWindowDispatcher<T> {
         Ok(false)
     }
 
-    /// Unsupported, always returns true
     fn is_resizable(&self) -> Result<bool> {
-        Ok(true)
+        self.webview
+            .lock()
+            .unwrap()
+            .is_resizable()
+            .map_err(|_| Error::FailedToSendMessage)
     }
 
-    /// Unsupported, always returns true
     fn is_maximizable(&self) -> Result<bool> {
-        Ok(true)
+        self.webview
+            .lock()
+            .unwrap()
+            .is_maximizable()
+            .map_err(|_| Error::FailedToSendMessage)
     }
 
-    /// Unsupported, always returns true
     fn is_minimizable(&self) -> Result<bool> {
-        Ok(true)
+        self.webview
+            .lock()
+            .unwrap()
+            .is_minimizable()
+            .map_err(|_| Error::FailedToSendMessage)
     }
 
-    /// Unsupported, always returns true
     fn is_closable(&self) -> Result<bool> {
-        Ok(true)
+        self.webview
+            .lock()
+            .unwrap()
+            .is_closable()
+            .map_err(|_| Error::FailedToSendMessage)
     }
 
     fn is_visible(&self) -> Result<bool> {
@@ -507,9 +1197,10 @@ impl<T: UserEvent> WindowDispatch<T> for VersoWindowDispatcher<T> {
             .map_err(|_| Error::FailedToSendMessage)
     }
 
-    /// Unsupported, always returns [`None`]
+    /// Returns the monitor the center of the window is on,
+    /// or [`None`] if it's not on any monitor, see [`VersoWindowDispatcher::monitor_from_window`]
     fn current_monitor(&self) -> Result<Option<Monitor>> {
-        Ok(None)
+        self.monitor_from_window()
     }
 
     fn primary_monitor(&self) -> Result<Option<Monitor>> {
@@ -522,9 +1213,9 @@ impl<T: UserEvent> WindowDispatch<T> for VersoWindowDispatcher<T> {
             .run_on_main_thread_with_event_loop(move |e| e.tauri_monitor_from_point(x, y))
     }
 
+    /// Returns the last known monitors immediately when called from a background thread
     fn available_monitors(&self) -> Result<Vec<Monitor>> {
-        self.context
-            .run_on_main_thread_with_event_loop(|e| e.tauri_available_monitors())
+        self.context.available_monitors()
     }
 
     fn theme(&self) -> Result<Theme> {
@@ -591,23 +1282,39 @@ impl<T: UserEvent> WindowDispatch<T> for VersoWindowDispatcher<T> {
         Err(tauri_runtime::Error::CreateWindow)
     }
 
-    /// Unsupported, has no effect when called
     fn set_resizable(&self, resizable: bool) -> Result<()> {
+        self.webview
+            .lock()
+            .unwrap()
+            .set_resizable(resizable)
+            .map_err(|_| Error::FailedToSendMessage)?;
         Ok(())
     }
 
-    /// Unsupported, has no effect when called
     fn set_maximizable(&self, maximizable: bool) -> Result<()> {
+        self.webview
+            .lock()
+            .unwrap()
+            .set_maximizable(maximizable)
+            .map_err(|_| Error::FailedToSendMessage)?;
         Ok(())
     }
 
-    /// Unsupported, has no effect when called
     fn set_minimizable(&self, minimizable: bool) -> Result<()> {
+        self.webview
+            .lock()
+            .unwrap()
+            .set_minimizable(minimizable)
+            .map_err(|_| Error::FailedToSendMessage)?;
         Ok(())
     }
 
-    /// Unsupported, has no effect when called
     fn set_closable(&self, closable: bool) -> Result<()> {
+        self.webview
+            .lock()
+            .unwrap()
+            .set_closable(closable)
+            .map_err(|_| Error::FailedToSendMessage)?;
         Ok(())
     }
 
@@ -730,13 +1437,13 @@ impl<T: UserEvent> WindowDispatch<T> for VersoWindowDispatcher<T> {
         Ok(())
     }
 
+    /// Sets the inner size of the window
+    ///
+    /// If the window is fullscreen, the size isn't applied right away but queued,
+    /// and applied once the window leaves fullscreen through [`WindowDispatch::set_fullscreen`]
+    /// or the OS (e.g. pressing Esc), only the last size queued is applied
     fn set_size(&self, size: Size) -> Result<()> {
-        self.webview
-            .lock()
-            .unwrap()
-            .set_size(size)
-            .map_err(|_| Error::FailedToSendMessage)?;
-        Ok(())
+        FullscreenState::set_size(&self.fullscreen, &self.webview, size)
     }
 
     /// Unsupported, has no effect when called
@@ -749,21 +1456,27 @@ impl<T: UserEvent> WindowDispatch<T> for VersoWindowDispatcher<T> {
         Ok(())
     }
 
+    /// Sets the outer position of the window
+    ///
+    /// If the window is fullscreen, the position isn't applied right away but queued,
+    /// and applied once the window leaves fullscreen through [`WindowDispatch::set_fullscreen`]
+    /// or the OS (e.g. pressing Esc), only the last position queued is applied
     fn set_position(&self, position: Position) -> Result<()> {
-        self.webview
-            .lock()
-            .unwrap()
-            .set_position(position)
-            .map_err(|_| Error::FailedToSendMessage)?;
-        Ok(())
+        FullscreenState::set_position(&self.fullscreen, &self.webview, position)
     }
 
+    /// Sets the window to fullscreen or back,
+    /// the size and position set while fullscreen are applied when leaving fullscreen
     fn set_fullscreen(&self, fullscreen: bool) -> Result<()> {
-        self.webview
-            .lock()
-            .unwrap()
-            .set_fullscreen(fullscreen)
+        let mut state = self.fullscreen.lock().unwrap();
+        let webview = self.webview.lock().unwrap();
+        if !fullscreen {
+            return state.leave(&*webview);
+        }
+        webview
+            .set_fullscreen(true)
             .map_err(|_| Error::FailedToSendMessage)?;
+        state.fullscreen = true;
         Ok(())
     }
 
@@ -791,14 +1504,21 @@ impl<T: UserEvent> WindowDispatch<T> for VersoWindowDispatcher<T> {
         Ok(())
     }
 
-    /// Unsupported, has no effect when called
+    /// Hides the cursor while it's over the window
     fn set_cursor_visible(&self, visible: bool) -> Result<()> {
-        Ok(())
+        self.webview
+            .lock()
+            .unwrap()
+            .set_cursor_visible(visible)
+            .map_err(|_| Error::FailedToSendMessage)
     }
 
-    /// Unsupported, has no effect when called
     fn set_cursor_icon(&self, icon: CursorIcon) -> Result<()> {
-        Ok(())
+        self.webview
+            .lock()
+            .unwrap()
+            .set_cursor_icon(to_verso_cursor_icon(icon))
+            .map_err(|_| Error::FailedToSendMessage)
     }
 
     /// Unsupported, has no effect when called
@@ -806,9 +1526,19 @@ impl<T: UserEvent> WindowDispatch<T> for VersoWindowDispatcher<T> {
         Ok(())
     }
 
-    /// Unsupported, has no effect when called
+    /// Lets the mouse events pass through the window to the windows beneath it
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Windows**: Makes the window a layered window (`WS_EX_LAYERED | WS_EX_TRANSPARENT`)
+    /// - **macOS**: Applies to the whole window, including the title bar
+    /// - **Linux**: Sets an empty input shape, which needs support from the window manager or compositor
     fn set_ignore_cursor_events(&self, ignore: bool) -> Result<()> {
-        Ok(())
+        self.webview
+            .lock()
+            .unwrap()
+            .set_ignore_cursor_events(ignore)
+            .map_err(|_| Error::FailedToSendMessage)
     }
 
     fn start_dragging(&self) -> Result<()> {
@@ -858,7 +1588,10 @@ impl<T: UserEvent> WindowDispatch<T> for VersoWindowDispatcher<T> {
         Ok(())
     }
 
+    /// Sets the theme of this window, or follows the app or system theme again with `None`
     fn set_theme(&self, theme: Option<Theme>) -> Result<()> {
+        *self.explicit_theme.lock().unwrap() = theme;
+        let theme = theme.or_else(|| self.context.default_theme());
         self.webview
             .lock()
             .unwrap()
@@ -900,3 +1633,103 @@ impl<T: UserEvent> WindowDispatch<T> for VersoWindowDispatcher<T> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct FakeWindow {
+        fullscreen: Cell<bool>,
+        sizes: RefCell<Vec<Size>>,
+        positions: RefCell<Vec<Position>>,
+    }
+
+    impl FullscreenWindow for FakeWindow {
+        fn is_fullscreen(&self) -> Result<bool> {
+            Ok(self.fullscreen.get())
+        }
+
+        fn set_fullscreen(&self, fullscreen: bool) -> Result<()> {
+            self.fullscreen.set(fullscreen);
+            Ok(())
+        }
+
+        fn set_size(&self, size: Size) -> Result<()> {
+            self.sizes.borrow_mut().push(size);
+            Ok(())
+        }
+
+        fn set_position(&self, position: Position) -> Result<()> {
+            self.positions.borrow_mut().push(position);
+            Ok(())
+        }
+    }
+
+    fn fullscreen() -> (Mutex<FullscreenState>, Mutex<FakeWindow>) {
+        let window = FakeWindow::default();
+        window.fullscreen.set(true);
+        let state = FullscreenState {
+            fullscreen: true,
+            ..Default::default()
+        };
+        (Mutex::new(state), Mutex::new(window))
+    }
+
+    #[test]
+    fn geometry_is_kept_while_fullscreen() {
+        let (state, window) = fullscreen();
+        let size = Size::Physical(PhysicalSize::new(800, 600));
+        let position = Position::Physical(PhysicalPosition::new(10, 20));
+        FullscreenState::set_size(&state, &window, size).unwrap();
+        FullscreenState::set_position(&state, &window, position).unwrap();
+        assert!(window.lock().unwrap().sizes.borrow().is_empty());
+        assert!(window.lock().unwrap().positions.borrow().is_empty());
+
+        state
+            .lock()
+            .unwrap()
+            .leave(&*window.lock().unwrap())
+            .unwrap();
+        let window = window.lock().unwrap();
+        assert!(!window.fullscreen.get());
+        assert_eq!(*window.sizes.borrow(), [size]);
+        assert_eq!(*window.positions.borrow(), [position]);
+    }
+
+    #[test]
+    fn leaving_fullscreen_through_the_os_applies_geometry() {
+        let (state, window) = fullscreen();
+        let pending = Size::Physical(PhysicalSize::new(800, 600));
+        FullscreenState::set_size(&state, &window, pending).unwrap();
+
+        // The user leaves fullscreen with Esc, versoview sends a resize
+        window.lock().unwrap().fullscreen.set(false);
+        FullscreenState::resized(&state, &window).unwrap();
+        assert!(!state.lock().unwrap().fullscreen);
+        assert_eq!(*window.lock().unwrap().sizes.borrow(), [pending]);
+
+        let size = Size::Physical(PhysicalSize::new(1024, 768));
+        let position = Position::Physical(PhysicalPosition::new(10, 20));
+        FullscreenState::set_size(&state, &window, size).unwrap();
+        FullscreenState::set_position(&state, &window, position).unwrap();
+        assert!(!state.lock().unwrap().fullscreen);
+        let window = window.lock().unwrap();
+        assert_eq!(*window.sizes.borrow(), [pending, size]);
+        assert_eq!(*window.positions.borrow(), [position]);
+    }
+    #[test]
+    fn entering_fullscreen_through_the_os_keeps_geometry() {
+        let state = Mutex::new(FullscreenState::default());
+        let window = Mutex::new(FakeWindow::default());
+        window.lock().unwrap().fullscreen.set(true);
+        FullscreenState::resized(&state, &window).unwrap();
+
+        let size = Size::Physical(PhysicalSize::new(800, 600));
+        FullscreenState::set_size(&state, &window, size).unwrap();
+        assert!(state.lock().unwrap().fullscreen);
+        assert!(window.lock().unwrap().sizes.borrow().is_empty());
+    }
+}
@@ -20,19 +20,77 @@
 //!   "bundle": {
 //!     "externalBin": [
 //!       "versoview/versoview"
-//!     ]
+//!     ],
+//!     "resources": {
+//!       "versoview/versoview-resources/": "versoview-resources/"
+//!     }
 //!   }
 //! }
 //! ```
 //!
+//! ## Resources
+//!
+//! Verso's resources are extracted to `./versoview/versoview-resources`,
+//! and copied next to the executable in the target directory (e.g. `target/debug/versoview-resources`),
+//! the runtime picks up a `versoview-resources` directory next to the versoview executable automatically,
+//! so there's no need to set the resources directory yourself in development,
+//! and on Windows, where the bundled resources are placed next to the executable
+//!
+//! The path to the extracted resources is also available to your crate
+//! through the `VERSO_RESOURCES_DIRECTORY` environment variable at compile time, e.g. `env!("VERSO_RESOURCES_DIRECTORY")`
+//!
+//! ## Flaky networks
+//!
+//! Failed downloads are retried with exponential backoff (1s, 2s, 4s, ...),
+//! set the `VERSOVIEW_DOWNLOAD_ATTEMPTS` environment variable to change the number of attempts (3 by default)
+//!
+//! ## Download progress
+//!
+//! The download progress is printed to stderr every few seconds, cargo hides the output of build scripts
+//! while they're running, so build with `cargo build -vv` to see it live
+//!
 
-use std::{io, path::PathBuf};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    thread::sleep,
+    time::{Duration, Instant},
+};
 
 pub use versoview_build;
 
+/// The name of the resources directory, the runtime looks for it next to the versoview executable
+pub const RESOURCES_DIRECTORY_NAME: &str = "versoview-resources";
+
+/// The environment variable for the number of download attempts, see [the crate docs](crate#flaky-networks)
+const DOWNLOAD_ATTEMPTS_ENV: &str = "VERSOVIEW_DOWNLOAD_ATTEMPTS";
+const DEFAULT_DOWNLOAD_ATTEMPTS: u32 = 3;
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+/// Report the download progress at most this often
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(2);
+
 /// Downloads and extracts the pre-built versoview executable
-/// to `./versoview/versoview(.exe)` relative to the directory containing your `Cargo.toml` file
+/// to `./versoview/versoview(.exe)` relative to the directory containing your `Cargo.toml` file,
+/// and Verso's resources to `./versoview/versoview-resources`, see [the crate docs](crate#resources)
+///
+/// This gets [`versoview_build::VERSO_VERSION`], use [`get_verso_as_external_bin_version`] to pin a different version
 pub fn get_verso_as_external_bin() -> io::Result<()> {
+    get_verso_as_external_bin_version(versoview_build::VERSO_VERSION)
+}
+
+/// Same as [`get_verso_as_external_bin`] but gets a specific versoview version,
+/// which must be one of [`versoview_build::VERSO_VERSIONS`]
+///
+/// Note the runtime checks the versoview version when it starts it,
+/// and fails to create windows with a version its IPC doesn't work with
+pub fn get_verso_as_external_bin_version(version: &str) -> io::Result<()> {
+    if !versoview_build::VERSO_VERSIONS.contains(&version) {
+        return Err(io::Error::other(format!(
+            "Unknown versoview version {version}, the available versions are {}",
+            versoview_build::VERSO_VERSIONS.join(", ")
+        )));
+    }
+
     let target_os = std::env::var("CARGO_CFG_TARGET_OS").unwrap();
     if target_os == "android" || target_os == "ios" {
         return Err(io::Error::other(
@@ -48,22 +106,139 @@ pub fn get_verso_as_external_bin() -> io::Result<()> {
     let extension = if cfg!(windows) { ".exe" } else { "" };
     let output_executable = output_directory.join(format!("versoview-{target_triple}{extension}"));
     let output_version = output_directory.join("versoview-version.txt");
+    let output_resources = output_directory.join(RESOURCES_DIRECTORY_NAME);
 
-    if std::fs::exists(&output_executable)?
-        && std::fs::read_to_string(&output_version).unwrap_or_default()
-            == versoview_build::VERSO_VERSION
-    {
-        return Ok(());
-    }
+    let up_to_date = std::fs::exists(&output_executable)?
+        && std::fs::exists(&output_resources)?
+        && std::fs::read_to_string(&output_version).unwrap_or_default() == version;
+    if !up_to_date {
+        with_retries("versoview", || {
+            let mut progress = DownloadProgress::new("versoview");
+            versoview_build::download_and_extract_verso_version_with_progress(
+                &output_directory,
+                version,
+                |downloaded, total| progress.report(downloaded, total),
+            )
+        })?;
+        with_retries("Verso's resources", || {
+            if std::fs::exists(&output_resources)? {
+                std::fs::remove_dir_all(&output_resources)?;
+            }
+            let mut progress = DownloadProgress::new("Verso's resources");
+            versoview_build::download_and_extract_verso_resources_version_with_progress(
+                &output_resources,
+                version,
+                |downloaded, total| progress.report(downloaded, total),
+            )
+        })?;
 
-    versoview_build::download_and_extract_verso(&output_directory)?;
+        let extracted_versoview_path = output_directory.join(format!("versoview{extension}"));
+        std::fs::rename(extracted_versoview_path, &output_executable)?;
+        std::fs::write(&output_version, version)?;
+    }
 
-    let extracted_versoview_path = output_directory.join(format!("versoview{extension}"));
-    std::fs::rename(extracted_versoview_path, &output_executable)?;
-    std::fs::write(&output_version, versoview_build::VERSO_VERSION)?;
+    // Like how `tauri-build` copies the external binaries next to the executable,
+    // so the runtime can find the resources next to versoview in development
+    let out_directory = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    if let Some(target_directory) = out_directory.ancestors().nth(3) {
+        let target_resources = target_directory.join(RESOURCES_DIRECTORY_NAME);
+        if std::fs::exists(&target_resources)? {
+            std::fs::remove_dir_all(&target_resources)?;
+        }
+        copy_dir_all(&output_resources, &target_resources)?;
+    }
 
     println!("cargo:rerun-if-changed={}", output_executable.display());
     println!("cargo:rerun-if-changed={}", output_version.display());
+    println!(
+        "cargo:rustc-env=VERSO_RESOURCES_DIRECTORY={}",
+        output_resources.display()
+    );
+
+    Ok(())
+}
+
+/// Runs the download, retrying with exponential backoff if it fails,
+/// up to the number of attempts from the `VERSOVIEW_DOWNLOAD_ATTEMPTS` environment variable
+fn with_retries(name: &str, mut download: impl FnMut() -> io::Result<()>) -> io::Result<()> {
+    println!("cargo:rerun-if-env-changed={DOWNLOAD_ATTEMPTS_ENV}");
+    let attempts = std::env::var(DOWNLOAD_ATTEMPTS_ENV)
+        .ok()
+        .and_then(|attempts| attempts.parse().ok())
+        .unwrap_or(DEFAULT_DOWNLOAD_ATTEMPTS)
+        .max(1);
+    let mut delay = INITIAL_RETRY_DELAY;
+    let mut attempt = 1;
+    let error = loop {
+        let error = match download() {
+            Ok(()) => return Ok(()),
+            Err(error) => error,
+        };
+        if attempt >= attempts {
+            break error;
+        }
+        println!(
+            "cargo:warning=Failed to download {name} (attempt {attempt} of {attempts}): {error}, retrying in {delay:?}"
+        );
+        sleep(delay);
+        delay *= 2;
+        attempt += 1;
+    };
+    Err(io::Error::new(
+        error.kind(),
+        format!("Failed to download {name} after {attempts} attempts: {error}"),
+    ))
+}
+
+/// Prints the download progress to stderr periodically, so the build doesn't look stuck on slow connections,
+/// only visible with `cargo build -vv`, see [the crate docs](crate#download-progress)
+///
+/// Not `cargo:warning=` since cargo only shows those after the build script finishes
+struct DownloadProgress {
+    name: &'static str,
+    last_report: Option<Instant>,
+}
+
+impl DownloadProgress {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            last_report: None,
+        }
+    }
 
+    /// `total` is `None` if the server didn't send the size
+    fn report(&mut self, downloaded: u64, total: Option<u64>) {
+        let finished = total.is_some_and(|total| downloaded >= total);
+        if !finished
+            && self
+                .last_report
+                .is_some_and(|last_report| last_report.elapsed() < PROGRESS_INTERVAL)
+        {
+            return;
+        }
+        self.last_report = Some(Instant::now());
+        let name = self.name;
+        match total {
+            Some(total) if total > 0 => eprintln!(
+                "Downloading {name}: {}% ({downloaded} / {total} bytes)",
+                downloaded * 100 / total
+            ),
+            _ => eprintln!("Downloading {name}: {downloaded} bytes"),
+        }
+    }
+}
+
+fn copy_dir_all(from: &Path, to: &Path) -> io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let path = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_all(&entry.path(), &path)?;
+        } else {
+            std::fs::copy(entry.path(), path)?;
+        }
+    }
     Ok(())
 }
@@ -0,0 +1,60 @@
+//! A small test harness for the tests driving real versoview processes,
+//! the tests run one by one on the main thread, since that's where the event loop has to be created on some platforms,
+//! so the test binaries using this have `harness = false` in `Cargo.toml`
+//!
+//! These need versoview and a display, set the `VERSOVIEW_PATH` environment variable
+//! to the versoview executable to run them, they're skipped otherwise
+//!
+//! ```sh
+//! VERSOVIEW_PATH=../verso/target/debug/versoview cargo test --test versoview -- capture_element
+//! ```
+
+use std::{
+    panic::{AssertUnwindSafe, catch_unwind},
+    process::ExitCode,
+};
+
+/// A test, with its name and the function taking the state shared by the tests
+pub type Test<S> = (&'static str, fn(&mut S));
+
+/// Runs the tests with the state created by `setup`, calling `teardown` after each test,
+/// only runs the ones containing the first argument in their names if there's one (like `cargo test -- filter`)
+pub fn run<S>(setup: impl FnOnce() -> S, teardown: impl Fn(&mut S), tests: &[Test<S>]) -> ExitCode {
+    if std::env::var_os("VERSOVIEW_PATH").is_none() {
+        println!(
+            "skipping {} tests, set VERSOVIEW_PATH to the versoview executable to run them",
+            tests.len()
+        );
+        return ExitCode::SUCCESS;
+    }
+
+    let filter = std::env::args().skip(1).find(|arg| !arg.starts_with('-'));
+    let tests = tests
+        .iter()
+        .filter(|(name, _)| filter.as_ref().is_none_or(|filter| name.contains(filter)))
+        .collect::<Vec<_>>();
+    println!("\nrunning {} tests", tests.len());
+
+    let mut state = setup();
+    let mut failed = Vec::new();
+    for (name, test) in tests {
+        let passed = catch_unwind(AssertUnwindSafe(|| test(&mut state))).is_ok();
+        teardown(&mut state);
+        println!("test {name} ... {}", if passed { "ok" } else { "FAILED" });
+        if !passed {
+            failed.push(name);
+        }
+    }
+
+    if failed.is_empty() {
+        println!("\ntest result: ok");
+        ExitCode::SUCCESS
+    } else {
+        println!("\nfailures:");
+        for name in &failed {
+            println!("    {name}");
+        }
+        println!("\ntest result: FAILED. {} failed", failed.len());
+        ExitCode::FAILURE
+    }
+}
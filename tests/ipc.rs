@@ -0,0 +1,110 @@
+//! Tests the IPC of a Tauri app running on versoview,
+//! see the `common` module for how to run them
+
+mod common;
+
+use std::{
+    process::ExitCode,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use serde_json::{Value, json};
+use tauri::{
+    AppHandle, State, WebviewUrl, WebviewWindowBuilder,
+    ipc::{InvokeBody, Request, Response},
+};
+use tauri_runtime_verso::VersoRuntime;
+
+/// How long to wait for the page to report back before failing the test
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Sends the payloads through the IPC once the page is loaded and reports the results with the `report` command
+const SCRIPT: &str = r#"
+window.addEventListener('DOMContentLoaded', async () => {
+    const invoke = window.__TAURI_INTERNALS__.invoke
+    const payload = new Uint8Array(8 * 1024 * 1024).map((_, i) => i % 251)
+    let result
+    try {
+        const echoed = new Uint8Array(await invoke('echo', payload))
+        const sum = await invoke('sum', { payload: Array.from(payload.subarray(0, 4 * 1024 * 1024)) })
+        result = {
+            echoedLength: echoed.length,
+            echoedMatches: echoed.every((byte, i) => byte === payload[i]),
+            sum,
+        }
+    } catch (error) {
+        result = { error: String(error) }
+    }
+    await invoke('report', { result })
+})
+"#;
+
+/// The result reported by the page
+type Report = Arc<Mutex<Option<Value>>>;
+
+/// Sends the raw request body back
+#[tauri::command]
+fn echo(request: Request<'_>) -> Response {
+    match request.body() {
+        InvokeBody::Raw(bytes) => Response::new(bytes.clone()),
+        InvokeBody::Json(_) => Response::new(Vec::new()),
+    }
+}
+
+#[tauri::command]
+fn sum(payload: Vec<u8>) -> u64 {
+    payload.iter().map(|&byte| byte as u64).sum()
+}
+
+#[tauri::command]
+fn report(app: AppHandle<VersoRuntime>, state: State<'_, Report>, result: Value) {
+    state.lock().unwrap().replace(result);
+    app.exit(0);
+}
+
+fn large_payloads_go_through_the_ipc(_: &mut ()) {
+    let report = Report::default();
+    let app = tauri_runtime_verso::builder()
+        .manage(report.clone())
+        .invoke_handler(tauri::generate_handler![echo, sum, report])
+        .setup(|app| {
+            WebviewWindowBuilder::new(app, "main", WebviewUrl::default())
+                .initialization_script(SCRIPT)
+                .build()?;
+            Ok(())
+        })
+        .build(tauri::test::mock_context(tauri::test::noop_assets()))
+        .unwrap();
+    let app_handle = app.handle().clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(TIMEOUT);
+        app_handle.exit(1);
+    });
+    assert_eq!(
+        app.run_return(|_, _| {}),
+        0,
+        "timed out waiting for the page to report back"
+    );
+
+    let expected_sum = (0..4 * 1024 * 1024u64).map(|i| i % 251).sum::<u64>();
+    assert_eq!(
+        report.lock().unwrap().take().unwrap(),
+        json!({
+            "echoedLength": 8 * 1024 * 1024,
+            "echoedMatches": true,
+            "sum": expected_sum,
+        })
+    );
+}
+
+fn main() -> ExitCode {
+    common::run(
+        || (),
+        |_| {},
+        &[(
+            "large_payloads_go_through_the_ipc",
+            large_payloads_go_through_the_ipc,
+        )],
+    )
+}
@@ -0,0 +1,531 @@
+//! Tests driving windows in real versoview processes through the runtime,
+//! see the `common` module for how to run them
+
+mod common;
+
+use std::{
+    borrow::Cow,
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    process::ExitCode,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+        mpsc::channel,
+    },
+    time::{Duration, Instant},
+};
+
+use tauri::EventLoopMessage;
+use tauri_runtime::{
+    ExitRequestedEventAction, RunEvent, Runtime, RuntimeInitArgs, WebviewDispatch, WindowDispatch,
+    dpi::{LogicalSize, PhysicalPosition, PhysicalSize, Position, Size},
+    webview::{PageLoadEvent, PendingWebview, WebviewAttributes},
+    window::{PendingWindow, RawWindow, WindowBuilder, WindowEvent, WindowId},
+};
+use tauri_runtime_verso::{
+    CaptureElementError, SessionState, VersoRuntime, VersoWebviewDispatcher, VersoWindowBuilder,
+    VersoWindowDispatcher,
+};
+use tauri_utils::config::WebviewUrl;
+
+/// How long to wait for versoview to do something before failing the test
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The scheme of the custom protocol serving the test pages
+const TEST_SCHEME: &str = "test";
+
+type TestWebview = PendingWebview<EventLoopMessage, VersoRuntime>;
+
+/// The runtime shared by the tests, since there can only be one event loop in a process
+struct TestRuntime {
+    runtime: VersoRuntime,
+    /// The windows created by the current test, closed after it
+    windows: Vec<(WindowId, VersoWindowDispatcher<EventLoopMessage>)>,
+    /// The window events emitted during the current test, with the labels of their windows
+    window_events: Vec<(String, WindowEvent)>,
+}
+
+/// A window created by [`TestRuntime::create_window`]
+struct TestWindow {
+    id: WindowId,
+    window: VersoWindowDispatcher<EventLoopMessage>,
+    webview: VersoWebviewDispatcher<EventLoopMessage>,
+}
+
+impl TestRuntime {
+    fn new() -> Self {
+        Self {
+            runtime: VersoRuntime::new(RuntimeInitArgs::default()).unwrap(),
+            windows: Vec::new(),
+            window_events: Vec::new(),
+        }
+    }
+
+    /// Processes the events that came in, or waits a bit for new ones,
+    /// this keeps the app running when the last window is closed, so the next test can still use the runtime
+    fn pump(&mut self) {
+        let Self {
+            runtime,
+            window_events,
+            ..
+        } = self;
+        let exit_code = runtime.pump_events(Duration::from_millis(50), |event| match event {
+            RunEvent::ExitRequested { tx, .. } => {
+                let _ = tx.send(ExitRequestedEventAction::Prevent);
+            }
+            RunEvent::WindowEvent { label, event } => window_events.push((label, event)),
+            _ => {}
+        });
+        assert_eq!(exit_code, None, "the app exited");
+    }
+
+    /// Pumps the events until `condition` is met, panics if it's not met within [`TIMEOUT`]
+    fn wait_until(&mut self, what: &str, mut condition: impl FnMut(&mut Self) -> bool) {
+        let deadline = Instant::now() + TIMEOUT;
+        while !condition(self) {
+            assert!(Instant::now() < deadline, "timed out waiting until {what}");
+            self.pump();
+        }
+    }
+
+    /// Creates a window showing `html` from the [`TEST_SCHEME`] protocol and waits for the page to load,
+    /// `configure` can change the webview before the window is created, e.g. to register more protocols
+    fn create_window(
+        &mut self,
+        label: &str,
+        window_builder: VersoWindowBuilder,
+        html: &'static str,
+        configure: impl FnOnce(&mut TestWebview),
+    ) -> TestWindow {
+        let mut webview =
+            PendingWebview::new(WebviewAttributes::new(WebviewUrl::default()), label).unwrap();
+        webview.url = test_url("/");
+        serve(&mut webview, TEST_SCHEME, move |_request| {
+            html_response(html)
+        });
+        let loaded = Arc::new(AtomicBool::new(false));
+        let page_loaded = loaded.clone();
+        webview.on_page_load_handler = Some(Box::new(move |_url, event| {
+            if event == PageLoadEvent::Finished {
+                page_loaded.store(true, Ordering::Relaxed);
+            }
+        }));
+        configure(&mut webview);
+
+        let mut pending = PendingWindow::new(window_builder, label).unwrap();
+        pending.webview = Some(webview);
+        let window = self
+            .runtime
+            .create_window(pending, None::<fn(RawWindow<'_>)>)
+            .unwrap();
+        self.windows.push((window.id, window.dispatcher.clone()));
+        self.wait_until(&format!("window ({label}) is loaded"), |_| {
+            loaded.load(Ordering::Relaxed)
+        });
+
+        TestWindow {
+            id: window.id,
+            window: window.dispatcher,
+            webview: window.webview.unwrap().webview.dispatcher,
+        }
+    }
+
+    /// Evaluates the script in the page and waits for the result
+    fn eval(
+        &mut self,
+        webview: &VersoWebviewDispatcher<EventLoopMessage>,
+        script: &str,
+    ) -> serde_json::Value {
+        let (tx, rx) = channel();
+        webview
+            .eval_script_with_callback(script, move |result| {
+                let _ = tx.send(result);
+            })
+            .unwrap();
+        let mut result = None;
+        self.wait_until(&format!("`{script}` is evaluated"), |_| {
+            result = rx.try_recv().ok();
+            result.is_some()
+        });
+        result.unwrap().unwrap()
+    }
+
+    /// Returns if a window event matching `predicate` is emitted for the window during this test
+    fn has_window_event(&self, label: &str, predicate: impl Fn(&WindowEvent) -> bool) -> bool {
+        self.window_events
+            .iter()
+            .any(|(event_label, event)| event_label == label && predicate(event))
+    }
+
+    /// Closes the windows created by the last test and clears its events
+    fn reset(&mut self) {
+        for (id, window) in std::mem::take(&mut self.windows) {
+            let _ = window.destroy();
+            self.wait_until("the window is closed", |test| {
+                !test.runtime.context.has_window(id)
+            });
+        }
+        self.window_events.clear();
+    }
+}
+
+/// The URL of `path` on the [`TEST_SCHEME`] protocol
+fn test_url(path: &str) -> String {
+    if cfg!(windows) {
+        format!("http://{TEST_SCHEME}.localhost{path}")
+    } else {
+        format!("{TEST_SCHEME}://localhost{path}")
+    }
+}
+
+/// Registers the custom protocol `scheme` on the webview, responding with `handler`
+fn serve(
+    webview: &mut TestWebview,
+    scheme: &str,
+    handler: impl Fn(http::Request<Vec<u8>>) -> http::Response<Cow<'static, [u8]>>
+    + Send
+    + Sync
+    + 'static,
+) {
+    webview.register_uri_scheme_protocol(scheme, move |_label, request, responder| {
+        responder(handler(request))
+    });
+}
+
+/// Serves `body` over HTTP on a random local port on a background thread, allowing any origin to fetch it,
+/// returns the port and the request heads (the request line and the headers) it gets
+fn serve_http(content_type: &'static str, body: String) -> (u16, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let server_requests = requests.clone();
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let head = BufReader::new(&mut stream)
+                .lines()
+                .map_while(Result::ok)
+                .take_while(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n");
+            server_requests.lock().unwrap().push(head);
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+        }
+    });
+    (port, requests)
+}
+
+fn html_response(html: &'static str) -> http::Response<Cow<'static, [u8]>> {
+    http::Response::builder()
+        .header(http::header::CONTENT_TYPE, "text/html")
+        .body(Cow::Borrowed(html.as_bytes()))
+        .unwrap()
+}
+
+fn pump_events_does_not_consume_the_runtime(test: &mut TestRuntime) {
+    let window = test.create_window(
+        "pump-events",
+        VersoWindowBuilder::new().inner_size(400., 300.),
+        "<p>pump_events</p>",
+        |_| {},
+    );
+    window
+        .window
+        .set_size(Size::Logical(LogicalSize::new(500., 400.)))
+        .unwrap();
+    test.wait_until("the window is resized", |test| {
+        test.has_window_event("pump-events", |event| {
+            matches!(event, WindowEvent::Resized(_))
+        })
+    });
+    // The runtime is still there to use after pumping the events
+    assert_eq!(
+        test.eval(&window.webview, "document.querySelector('p').textContent"),
+        "pump_events"
+    );
+    assert!(test.runtime.context.has_window(window.id));
+    assert!(
+        test.runtime
+            .handle()
+            .window_labels()
+            .contains(&"pump-events".to_owned())
+    );
+}
+
+/// Exports the session with the windows sorted by label
+fn export_session(test: &TestRuntime) -> SessionState {
+    let mut session = test.runtime.handle().export_session().unwrap();
+    session.windows.sort_by(|a, b| a.label.cmp(&b.label));
+    session
+}
+
+fn session_is_restored(test: &mut TestRuntime) {
+    for (label, path, x, y, width, height, zoom) in [
+        ("session-1", "/one", 100, 100, 640, 480, 1.5),
+        ("session-2", "/two", 200, 150, 500, 400, 0.8),
+    ] {
+        let window = test.create_window(
+            label,
+            VersoWindowBuilder::new(),
+            "<p>session</p>",
+            |webview| webview.url = test_url(path),
+        );
+        window
+            .window
+            .set_position(Position::Physical(PhysicalPosition::new(x, y)))
+            .unwrap();
+        window
+            .window
+            .set_size(Size::Physical(PhysicalSize::new(width, height)))
+            .unwrap();
+        window.webview.set_zoom(zoom).unwrap();
+        test.wait_until(&format!("window ({label}) is resized"), |test| {
+            test.has_window_event(label, |event| {
+                matches!(event, WindowEvent::Resized(size) if *size == PhysicalSize::new(width, height))
+            })
+        });
+    }
+
+    let session = export_session(test);
+    assert_eq!(session.windows.len(), 2);
+    for (state, url) in session
+        .windows
+        .iter()
+        .zip([test_url("/one"), test_url("/two")])
+    {
+        assert_eq!(state.url, url);
+    }
+    assert_eq!(
+        (session.windows[0].width, session.windows[0].height),
+        (640, 480)
+    );
+    assert_eq!(session.windows[0].zoom, 1.5);
+    assert_eq!(
+        (session.windows[1].width, session.windows[1].height),
+        (500, 400)
+    );
+    assert_eq!(session.windows[1].zoom, 0.8);
+
+    // Close the windows like the app is relaunched
+    test.reset();
+
+    // The runtime can't create the windows on its own, it returns the ones to create
+    let missing_windows = test
+        .runtime
+        .handle()
+        .import_session(session.clone())
+        .unwrap();
+    assert_eq!(missing_windows, session.windows);
+    for state in &missing_windows {
+        test.create_window(
+            &state.label,
+            VersoWindowBuilder::new(),
+            "<p>session</p>",
+            |webview| webview.url = state.url.clone(),
+        );
+    }
+    // Then the windows that are open get restored
+    let missing_windows = test
+        .runtime
+        .handle()
+        .import_session(session.clone())
+        .unwrap();
+    assert!(missing_windows.is_empty());
+    test.wait_until("the session is restored", |test| {
+        export_session(test) == session
+    });
+}
+
+/// Creates a window with `window_builder`, overriding the user agent with `configure`,
+/// returns the `User-Agent` the page request comes with and `navigator.userAgent` in the page
+fn user_agent(
+    test: &mut TestRuntime,
+    label: &str,
+    window_builder: VersoWindowBuilder,
+    configure: impl FnOnce(&mut TestWebview),
+) -> (String, serde_json::Value) {
+    let user_agents = Arc::new(Mutex::new(Vec::new()));
+    let request_user_agents = user_agents.clone();
+    let window = test.create_window(label, window_builder, "", |webview| {
+        serve(webview, TEST_SCHEME, move |request| {
+            request_user_agents.lock().unwrap().push(
+                request
+                    .headers()
+                    .get(http::header::USER_AGENT)
+                    .map(|user_agent| user_agent.to_str().unwrap().to_owned()),
+            );
+            html_response("<p>user agent</p>")
+        });
+        configure(webview);
+    });
+    let request_user_agent = user_agents.lock().unwrap()[0].clone().unwrap();
+    let page_user_agent = test.eval(&window.webview, "navigator.userAgent");
+    (request_user_agent, page_user_agent)
+}
+
+fn user_agent_is_sent_with_the_requests(test: &mut TestRuntime) {
+    let (request_user_agent, page_user_agent) = user_agent(
+        test,
+        "user-agent-attributes",
+        VersoWindowBuilder::new(),
+        |webview| {
+            webview.webview_attributes.user_agent = Some("tauri-runtime-verso-test/1".to_owned());
+        },
+    );
+    assert_eq!(request_user_agent, "tauri-runtime-verso-test/1");
+    assert_eq!(page_user_agent, "tauri-runtime-verso-test/1");
+
+    let (request_user_agent, page_user_agent) = user_agent(
+        test,
+        "user-agent-builder",
+        VersoWindowBuilder::new().user_agent("tauri-runtime-verso-test/2"),
+        |_| {},
+    );
+    assert_eq!(request_user_agent, "tauri-runtime-verso-test/2");
+    assert_eq!(page_user_agent, "tauri-runtime-verso-test/2");
+}
+
+fn zoom_only_scales_the_content(test: &mut TestRuntime) {
+    let window = test.create_window(
+        "zoom",
+        VersoWindowBuilder::new().inner_size(800., 600.),
+        "<p>zoom</p>",
+        |_| {},
+    );
+    let inner_size = window.window.inner_size().unwrap();
+    let scale_factor = window.window.scale_factor().unwrap();
+    let inner_width = test
+        .eval(&window.webview, "window.innerWidth")
+        .as_f64()
+        .unwrap();
+
+    window.webview.set_zoom(2.0).unwrap();
+    test.wait_until("the page is zoomed", |test| {
+        test.eval(&window.webview, "window.devicePixelRatio")
+            .as_f64()
+            == Some(scale_factor * 2.)
+    });
+
+    // The content is twice as large, so half as many CSS pixels fit in the window
+    let zoomed_inner_width = test
+        .eval(&window.webview, "window.innerWidth")
+        .as_f64()
+        .unwrap();
+    assert!(
+        (zoomed_inner_width - inner_width / 2.).abs() <= 1.,
+        "the page is {zoomed_inner_width} CSS pixels wide after zooming from {inner_width}"
+    );
+    // While the window stays the same
+    assert_eq!(window.window.inner_size().unwrap(), inner_size);
+    assert_eq!(window.window.scale_factor().unwrap(), scale_factor);
+}
+
+fn cross_origin_fetch_keeps_its_origin(test: &mut TestRuntime) {
+    let (data_port, data_requests) = serve_http("text/plain", "data".to_owned());
+    let (page_port, _) = serve_http(
+        "text/html",
+        format!("<script>fetch('http://127.0.0.1:{data_port}/data')</script>"),
+    );
+    test.create_window("origin", VersoWindowBuilder::new(), "", |webview| {
+        webview.url = format!("http://127.0.0.1:{page_port}/");
+    });
+    test.wait_until("the data is fetched", |_| {
+        !data_requests.lock().unwrap().is_empty()
+    });
+
+    let request = data_requests.lock().unwrap()[0].clone();
+    let origin = request
+        .lines()
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.eq_ignore_ascii_case("origin")
+                .then(|| value.trim().to_owned())
+        })
+        .unwrap_or_else(|| panic!("no Origin header in the request:\n{request}"));
+    assert_eq!(origin, format!("http://127.0.0.1:{page_port}"));
+}
+
+fn scripts_do_not_run_with_javascript_disabled(test: &mut TestRuntime) {
+    const HTML: &str = "<title>before</title><script>document.title = 'after'</script>";
+    let enabled = test.create_window(
+        "javascript-enabled",
+        VersoWindowBuilder::new(),
+        HTML,
+        |_| {},
+    );
+    let disabled = test.create_window(
+        "javascript-disabled",
+        VersoWindowBuilder::new().javascript_enabled(false),
+        HTML,
+        |_| {},
+    );
+    test.wait_until("the script changes the title", |_| {
+        enabled.window.title().unwrap() == "after"
+    });
+    assert_eq!(disabled.window.title().unwrap(), "before");
+}
+
+fn capture_element_matches_the_element_size(test: &mut TestRuntime) {
+    let window = test.create_window(
+        "capture-element",
+        VersoWindowBuilder::new(),
+        r#"<body style="margin: 0; height: 2000px">
+<div id="chart" style="width: 120px; height: 80px; margin-top: 1500px; background: teal"></div>
+</body>"#,
+        |_| {},
+    );
+    let scale_factor = window.window.scale_factor().unwrap();
+
+    // The element is scrolled out of view, but it's still captured as a whole
+    let image = window.webview.capture_element("#chart").unwrap();
+    assert_eq!(image.width(), (120. * scale_factor).round() as u32);
+    assert_eq!(image.height(), (80. * scale_factor).round() as u32);
+    assert_eq!(
+        image.rgba().len(),
+        image.width() as usize * image.height() as usize * 4
+    );
+
+    assert_eq!(
+        window.webview.capture_element("#missing").unwrap_err(),
+        CaptureElementError::NoMatch("#missing".to_owned())
+    );
+    assert_eq!(
+        window.webview.capture_element("#").unwrap_err(),
+        CaptureElementError::InvalidSelector("#".to_owned())
+    );
+}
+
+fn main() -> ExitCode {
+    common::run(
+        TestRuntime::new,
+        TestRuntime::reset,
+        &[
+            (
+                "pump_events_does_not_consume_the_runtime",
+                pump_events_does_not_consume_the_runtime,
+            ),
+            ("session_is_restored", session_is_restored),
+            (
+                "user_agent_is_sent_with_the_requests",
+                user_agent_is_sent_with_the_requests,
+            ),
+            ("zoom_only_scales_the_content", zoom_only_scales_the_content),
+            (
+                "cross_origin_fetch_keeps_its_origin",
+                cross_origin_fetch_keeps_its_origin,
+            ),
+            (
+                "scripts_do_not_run_with_javascript_disabled",
+                scripts_do_not_run_with_javascript_disabled,
+            ),
+            (
+                "capture_element_matches_the_element_size",
+                capture_element_matches_the_element_size,
+            ),
+        ],
+    )
+}